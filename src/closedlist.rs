@@ -0,0 +1,19 @@
+pub mod hash_list;
+pub mod vec_list;
+
+use crate::cost::Cost;
+
+pub trait ClosedList<T, K: Cost = f32> {
+    fn new() -> Self;
+    /// Adds the element, returning `false` if it was already present
+    fn add(&mut self, element: T) -> bool;
+    fn contains(&self, element: &T) -> bool;
+    /// Lowest known cost of the element. Elements added without a cost have
+    /// `Cost::MAX`
+    fn get_cost(&self, element: &T) -> Option<K>;
+    /// Adds the element, or updates its cost if it was already present
+    fn set_cost(&mut self, element: T, cost: K);
+    fn is_empty(&self) -> bool;
+    fn len(&self) -> usize;
+    fn clear(&mut self);
+}
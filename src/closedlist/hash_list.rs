@@ -0,0 +1,138 @@
+use std::collections::hash_map::Entry;
+use std::collections::HashMap;
+use std::hash::Hash;
+
+use super::ClosedList;
+use crate::cost::Cost;
+
+/// Closed list backed by a hash map, with constant time membership
+/// checks for states that implement `Hash + Eq`
+#[derive(Debug)]
+pub struct HashList<T, K = f32> {
+    elements: HashMap<T, K>,
+}
+
+impl<T: Hash + Eq, K: Cost> HashList<T, K> {
+    pub fn new() -> Self {
+        HashList {
+            elements: HashMap::new(),
+        }
+    }
+}
+
+impl<T: Hash + Eq, K: Cost> Default for HashList<T, K> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Hash + Eq, K: Cost> ClosedList<T, K> for HashList<T, K> {
+    fn new() -> Self {
+        HashList::new()
+    }
+
+    fn add(&mut self, element: T) -> bool {
+        match self.elements.entry(element) {
+            Entry::Occupied(_) => false,
+            Entry::Vacant(entry) => {
+                entry.insert(K::MAX);
+                true
+            }
+        }
+    }
+
+    fn contains(&self, element: &T) -> bool {
+        self.elements.contains_key(element)
+    }
+
+    fn get_cost(&self, element: &T) -> Option<K> {
+        self.elements.get(element).copied()
+    }
+
+    fn set_cost(&mut self, element: T, cost: K) {
+        self.elements.insert(element, cost);
+    }
+
+    fn is_empty(&self) -> bool {
+        self.elements.is_empty()
+    }
+
+    fn len(&self) -> usize {
+        self.elements.len()
+    }
+
+    fn clear(&mut self) {
+        self.elements.clear()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use crate::closedlist::hash_list::HashList;
+    use crate::closedlist::ClosedList;
+
+    #[test]
+    fn test_empty() {
+        let list: HashList<i32> = HashList::new();
+        assert!(list.is_empty());
+        assert_eq!(0, list.len());
+    }
+
+    #[test]
+    fn test_add() {
+        let mut list: HashList<i32> = HashList::new();
+        list.add(1);
+        assert_eq!(1, list.len());
+        list.add(2);
+        assert_eq!(2, list.len());
+        assert!(!list.is_empty());
+    }
+
+    #[test]
+    fn test_add_duplicate() {
+        let mut list: HashList<i32> = HashList::new();
+        assert!(list.add(1));
+        assert!(!list.add(1));
+        assert_eq!(1, list.len());
+    }
+
+    #[test]
+    fn test_contains() {
+        let mut list: HashList<i32> = HashList::new();
+        for i in 0..100 {
+            list.add(i);
+        }
+        for i in 0..100 {
+            assert!(list.contains(&i));
+        }
+        assert!(!list.contains(&100));
+    }
+
+    #[test]
+    fn test_cost() {
+        let mut list: HashList<i32> = HashList::new();
+        assert_eq!(None, list.get_cost(&1));
+        list.add(1);
+        assert_eq!(Some(f32::INFINITY), list.get_cost(&1));
+        list.set_cost(1, 2.0);
+        assert_eq!(Some(2.0), list.get_cost(&1));
+        list.set_cost(2, 3.0);
+        assert_eq!(Some(3.0), list.get_cost(&2));
+        assert!(!list.add(2));
+        assert_eq!(Some(3.0), list.get_cost(&2));
+        assert_eq!(2, list.len());
+    }
+
+    #[test]
+    fn test_clear() {
+        let mut list: HashList<i32> = HashList::new();
+        for i in 0..100 {
+            list.add(i);
+        }
+        assert!(!list.is_empty());
+        list.clear();
+        assert!(list.is_empty());
+        assert!(!list.contains(&0));
+    }
+}
@@ -0,0 +1,138 @@
+use super::ClosedList;
+use crate::cost::Cost;
+
+/// Closed list backed by a vector, with linear membership checks. It only
+/// requires `PartialEq`, as a fallback for states that cannot be hashed
+#[derive(Debug)]
+pub struct VecList<T, K = f32> {
+    elements: Vec<(T, K)>,
+}
+
+impl<T: PartialEq, K: Cost> VecList<T, K> {
+    pub fn new() -> Self {
+        VecList {
+            elements: Vec::new(),
+        }
+    }
+}
+
+impl<T: PartialEq, K: Cost> Default for VecList<T, K> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: PartialEq, K: Cost> ClosedList<T, K> for VecList<T, K> {
+    fn new() -> Self {
+        VecList::new()
+    }
+
+    fn add(&mut self, element: T) -> bool {
+        if self.contains(&element) {
+            return false;
+        }
+        self.elements.push((element, K::MAX));
+        true
+    }
+
+    fn contains(&self, element: &T) -> bool {
+        self.elements.iter().any(|(e, _)| e == element)
+    }
+
+    fn get_cost(&self, element: &T) -> Option<K> {
+        self.elements
+            .iter()
+            .find(|(e, _)| e == element)
+            .map(|(_, cost)| *cost)
+    }
+
+    fn set_cost(&mut self, element: T, cost: K) {
+        match self.elements.iter_mut().find(|(e, _)| *e == element) {
+            Some((_, current)) => *current = cost,
+            None => self.elements.push((element, cost)),
+        }
+    }
+
+    fn is_empty(&self) -> bool {
+        self.elements.is_empty()
+    }
+
+    fn len(&self) -> usize {
+        self.elements.len()
+    }
+
+    fn clear(&mut self) {
+        self.elements.clear()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use crate::closedlist::vec_list::VecList;
+    use crate::closedlist::ClosedList;
+
+    #[test]
+    fn test_empty() {
+        let list: VecList<i32> = VecList::new();
+        assert!(list.is_empty());
+        assert_eq!(0, list.len());
+    }
+
+    #[test]
+    fn test_add() {
+        let mut list: VecList<i32> = VecList::new();
+        list.add(1);
+        assert_eq!(1, list.len());
+        list.add(2);
+        assert_eq!(2, list.len());
+        assert!(!list.is_empty());
+    }
+
+    #[test]
+    fn test_add_duplicate() {
+        let mut list: VecList<i32> = VecList::new();
+        assert!(list.add(1));
+        assert!(!list.add(1));
+        assert_eq!(1, list.len());
+    }
+
+    #[test]
+    fn test_contains() {
+        let mut list: VecList<i32> = VecList::new();
+        for i in 0..100 {
+            list.add(i);
+        }
+        for i in 0..100 {
+            assert!(list.contains(&i));
+        }
+        assert!(!list.contains(&100));
+    }
+
+    #[test]
+    fn test_cost() {
+        let mut list: VecList<i32> = VecList::new();
+        assert_eq!(None, list.get_cost(&1));
+        list.add(1);
+        assert_eq!(Some(f32::INFINITY), list.get_cost(&1));
+        list.set_cost(1, 2.0);
+        assert_eq!(Some(2.0), list.get_cost(&1));
+        list.set_cost(2, 3.0);
+        assert_eq!(Some(3.0), list.get_cost(&2));
+        assert!(!list.add(2));
+        assert_eq!(Some(3.0), list.get_cost(&2));
+        assert_eq!(2, list.len());
+    }
+
+    #[test]
+    fn test_clear() {
+        let mut list: VecList<i32> = VecList::new();
+        for i in 0..100 {
+            list.add(i);
+        }
+        assert!(!list.is_empty());
+        list.clear();
+        assert!(list.is_empty());
+        assert!(!list.contains(&0));
+    }
+}
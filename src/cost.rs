@@ -0,0 +1,101 @@
+use std::cmp::Ordering;
+use std::fmt::{Debug, Display};
+use std::ops::Add;
+
+/// Numeric type of the costs of actions, paths and heuristics. Integer
+/// costs are exact and compare faster, floating point costs are ordered
+/// with `total_cmp`, so NaN does not break the priority lists.
+pub trait Cost: Copy + PartialOrd + Add<Output = Self> + Debug + Display {
+    const ZERO: Self;
+    /// Greater than any reachable cost, used for unknown costs
+    const MAX: Self;
+
+    fn total_cmp(&self, other: &Self) -> Ordering;
+    /// Cost multiplied by a real factor, rounded down for integers
+    fn scale(self, factor: f32) -> Self;
+    fn to_f64(self) -> f64;
+
+    /// Lower of both costs, named apart from `Ord::min` so both can be in
+    /// scope
+    fn min_cost(self, other: Self) -> Self {
+        match self.total_cmp(&other) {
+            Ordering::Greater => other,
+            _ => self,
+        }
+    }
+
+    fn max_cost(self, other: Self) -> Self {
+        match self.total_cmp(&other) {
+            Ordering::Less => other,
+            _ => self,
+        }
+    }
+}
+
+macro_rules! float_cost {
+    ($($t:ty),*) => {$(
+        impl Cost for $t {
+            const ZERO: Self = 0.0;
+            const MAX: Self = <$t>::INFINITY;
+
+            fn total_cmp(&self, other: &Self) -> Ordering {
+                <$t>::total_cmp(self, other)
+            }
+
+            fn scale(self, factor: f32) -> Self {
+                self * factor as $t
+            }
+
+            fn to_f64(self) -> f64 {
+                self as f64
+            }
+        }
+    )*};
+}
+
+macro_rules! integer_cost {
+    ($($t:ty),*) => {$(
+        impl Cost for $t {
+            const ZERO: Self = 0;
+            const MAX: Self = <$t>::MAX;
+
+            fn total_cmp(&self, other: &Self) -> Ordering {
+                self.cmp(other)
+            }
+
+            fn scale(self, factor: f32) -> Self {
+                (self as f64 * factor as f64).floor() as $t
+            }
+
+            fn to_f64(self) -> f64 {
+                self as f64
+            }
+        }
+    )*};
+}
+
+float_cost!(f32, f64);
+integer_cost!(u8, u16, u32, u64, usize, i8, i16, i32, i64, isize);
+
+#[cfg(test)]
+mod tests {
+
+    use std::cmp::Ordering;
+
+    use crate::cost::Cost;
+
+    #[test]
+    fn test_float_total_order() {
+        assert_eq!(Ordering::Less, Cost::total_cmp(&1.0_f32, &f32::NAN));
+        assert_eq!(Ordering::Less, Cost::total_cmp(&1.0_f32, &f32::MAX));
+        assert_eq!(2.0, 2.0_f64.min_cost(3.0));
+        assert_eq!(3.0, 2.0_f64.max_cost(3.0));
+    }
+
+    #[test]
+    fn test_integer_scale() {
+        assert_eq!(7, 5_u32.scale(1.5));
+        assert_eq!(5, 5_u32.scale(1.0));
+        assert_eq!(7.5, 5.0_f32.scale(1.5));
+    }
+}
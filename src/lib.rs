@@ -1,4 +1,6 @@
-mod openlist;
+pub mod closedlist;
+pub mod cost;
+pub mod openlist;
 pub mod search;
 
 pub fn add(left: usize, right: usize) -> usize {
@@ -2,6 +2,10 @@ pub mod prio_list;
 pub mod queue;
 pub mod stack;
 
+use crate::cost::Cost;
+
+/// Frontier of the uninformed searches, its order decides the order of
+/// expansion, see `Search::search_with_frontier`
 pub trait OpenList<T> {
     fn new() -> Self;
     fn add(&mut self, element: T);
@@ -12,8 +16,9 @@ pub trait OpenList<T> {
     fn clear(&mut self);
 }
 
-pub trait PriorityOpenList<T> {
-    fn add(&mut self, element: T, weigth: f32);
+/// Frontier ordered by a weight, the lowest first
+pub trait PriorityOpenList<T, K: Cost = f32> {
+    fn add(&mut self, element: T, weigth: K);
     fn get(&mut self) -> Option<T>;
     fn peek(&self) -> Option<&T>;
     fn is_empty(&self) -> bool;
@@ -1,68 +1,119 @@
-use std::collections::VecDeque;
+use std::cmp::Ordering;
+use std::collections::BinaryHeap;
 
 use super::PriorityOpenList;
+use crate::cost::Cost;
 
+/// Order in which elements with the same weight are returned
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TieBreak {
+    /// First added, first returned
+    Fifo,
+    /// Last added, first returned
+    Lifo,
+}
+
+#[derive(Debug)]
+struct Entry<T, K> {
+    weigth: K,
+    order: u64,
+    element: T,
+}
+
+impl<T, K: Cost> PartialEq for Entry<T, K> {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl<T, K: Cost> Eq for Entry<T, K> {}
+
+impl<T, K: Cost> PartialOrd for Entry<T, K> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<T, K: Cost> Ord for Entry<T, K> {
+    // reversed, as `BinaryHeap` is a max-heap and the lowest weight
+    // (and then the lowest order) has to come out first
+    fn cmp(&self, other: &Self) -> Ordering {
+        other
+            .weigth
+            .total_cmp(&self.weigth)
+            .then_with(|| other.order.cmp(&self.order))
+    }
+}
+
+/// Priority list backed by a binary heap: elements with lower weight
+/// come out first, and ties are resolved with the configured `TieBreak`
 #[derive(Debug)]
-pub struct PrioList<T> {
-    elements: VecDeque<T>,
-    values: VecDeque<f32>,
+pub struct PrioList<T, K = f32> {
+    heap: BinaryHeap<Entry<T, K>>,
+    tie_break: TieBreak,
+    sequence: u64,
 }
 
-impl<T> PrioList<T> {
+impl<T, K: Cost> PrioList<T, K> {
     pub fn new() -> Self {
+        PrioList::with_tie_break(TieBreak::Lifo)
+    }
+
+    pub fn with_tie_break(tie_break: TieBreak) -> Self {
         PrioList {
-            elements: VecDeque::new(),
-            values: VecDeque::new(),
+            heap: BinaryHeap::new(),
+            tie_break,
+            sequence: 0,
         }
     }
 }
 
-impl<T> PriorityOpenList<T> for PrioList<T> {
-    fn add(&mut self, element: T, weigth: f32) {
-        let mut pos: usize = match self.is_empty() {
-            true => 0,
-            false => self.elements.len(),
+impl<T, K: Cost> Default for PrioList<T, K> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T, K: Cost> PriorityOpenList<T, K> for PrioList<T, K> {
+    fn add(&mut self, element: T, weigth: K) {
+        let order = match self.tie_break {
+            TieBreak::Fifo => self.sequence,
+            TieBreak::Lifo => u64::MAX - self.sequence,
         };
-        for (i, v) in self.values.iter().enumerate() {
-            if v >= &weigth {
-                pos = i;
-                break;
-            }
-        }
-        self.elements.insert(pos, element);
-        self.values.insert(pos, weigth);
+        self.sequence += 1;
+        self.heap.push(Entry {
+            weigth,
+            order,
+            element,
+        });
     }
 
     fn get(&mut self) -> Option<T> {
-        self.values.pop_front();
-        self.elements.pop_front()
+        self.heap.pop().map(|entry| entry.element)
     }
 
     fn peek(&self) -> Option<&T> {
-        match self.is_empty() {
-            true => None,
-            false => self.elements.front(),
-        }
+        self.heap.peek().map(|entry| &entry.element)
     }
 
     fn is_empty(&self) -> bool {
-        self.elements.is_empty()
+        self.heap.is_empty()
     }
 
     fn len(&self) -> usize {
-        self.elements.len()
+        self.heap.len()
     }
 
     fn clear(&mut self) {
-        self.elements.clear();
-        self.values.clear();
+        self.heap.clear();
+        self.sequence = 0;
     }
 }
 
 #[cfg(test)]
 mod tests {
 
-    use crate::openlist::prio_list::PrioList;
+    use crate::openlist::prio_list::{PrioList, TieBreak};
     use crate::openlist::PriorityOpenList;
 
     #[test]
@@ -118,6 +169,7 @@ mod tests {
     }
 
     #[test]
+    #[allow(clippy::useless_vec)]
     fn test_sort() {
         let mut list: PrioList<i32> = PrioList::new();
         list.add(3, 3.3);
@@ -158,4 +210,73 @@ mod tests {
         }
         assert!(list.is_empty());
     }
+
+    #[test]
+    fn test_tie_break_lifo() {
+        let mut list: PrioList<i32> = PrioList::with_tie_break(TieBreak::Lifo);
+        list.add(1, 1.0);
+        list.add(2, 1.0);
+        list.add(0, 0.5);
+        list.add(3, 1.0);
+        for val in [0, 3, 2, 1] {
+            assert_eq!(Some(val), list.get());
+        }
+    }
+
+    #[test]
+    fn test_tie_break_fifo() {
+        let mut list: PrioList<i32> = PrioList::with_tie_break(TieBreak::Fifo);
+        list.add(1, 1.0);
+        list.add(2, 1.0);
+        list.add(0, 0.5);
+        list.add(3, 1.0);
+        for val in [0, 1, 2, 3] {
+            assert_eq!(Some(val), list.get());
+        }
+    }
+
+    #[test]
+    fn test_default_tie_break() {
+        let mut list: PrioList<i32> = PrioList::new();
+        list.add(1, 1.0);
+        list.add(2, 1.0);
+        assert_eq!(Some(2), list.get());
+    }
+
+    #[test]
+    fn test_integer_weights() {
+        let mut list: PrioList<i32, u32> = PrioList::with_tie_break(TieBreak::Fifo);
+        list.add(1, 2);
+        list.add(2, 1);
+        list.add(3, 2);
+        for val in [2, 1, 3] {
+            assert_eq!(Some(val), list.get());
+        }
+    }
+
+    #[test]
+    fn test_nan_weight() {
+        let mut list: PrioList<i32> = PrioList::new();
+        list.add(1, f32::NAN);
+        list.add(2, f32::INFINITY);
+        list.add(3, 1.0);
+        for val in [3, 2, 1] {
+            assert_eq!(Some(val), list.get());
+        }
+    }
+
+    #[test]
+    fn test_reverse_sequential_get() {
+        let mut list: PrioList<i32> = PrioList::new();
+        let nelem = 1000;
+        for i in (0..nelem).rev() {
+            list.add(i, (i % 10) as f32);
+        }
+        let mut last = -1.0;
+        while let Some(i) = list.get() {
+            let weigth = (i % 10) as f32;
+            assert!(weigth >= last);
+            last = weigth;
+        }
+    }
 }
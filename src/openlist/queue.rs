@@ -15,6 +15,12 @@ impl<T> Queue<T> {
     }
 }
 
+impl<T> Default for Queue<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl<T> OpenList<T> for Queue<T> {
     fn new() -> Self {
         Queue::new()
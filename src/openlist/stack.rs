@@ -2,6 +2,7 @@ use std::collections::VecDeque;
 
 use super::OpenList;
 
+#[derive(Debug)]
 pub struct Stack<T> {
     stack: VecDeque<T>,
 }
@@ -14,6 +15,12 @@ impl<T> Stack<T> {
     }
 }
 
+impl<T> Default for Stack<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl<T> OpenList<T> for Stack<T> {
     fn new() -> Self {
         Stack::new()
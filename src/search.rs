@@ -1,58 +1,287 @@
 use std::cmp;
-use std::fmt::{Display, Formatter, Result};
+use std::fmt::{Debug, Display, Formatter, Result};
+use std::hash::Hash;
 use std::marker;
+use std::panic::{self, AssertUnwindSafe};
 use std::rc::Rc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
 
+use crate::closedlist::hash_list::HashList;
+use crate::closedlist::vec_list::VecList;
+use crate::closedlist::ClosedList;
+use crate::cost::Cost;
 use crate::openlist::prio_list::PrioList;
+pub use crate::openlist::prio_list::TieBreak;
 use crate::openlist::queue::Queue;
 use crate::openlist::stack::Stack;
 use crate::openlist::{OpenList, PriorityOpenList};
 
+mod bidirectional;
+mod dot;
+mod error;
+mod node;
+mod observer;
+mod parallel;
+mod report;
+mod solution;
+mod solutions;
+
+pub use dot::DotExport;
+pub use error::{SearchError, SearchResult, SolutionsResult};
+pub use node::{Node, SharedPointer};
+use observer::{BoxedObserver, Hook};
+pub use observer::{Event, Observer};
+pub use parallel::{ParallelSearch, SharedSolution};
+pub use report::Report;
+pub use solution::Solution;
+pub use solutions::{BreadthSolutions, DepthSolutions, Solutions};
+
 /// Base action defintion applicable to a state
 pub trait Action {
-    fn cost(&self) -> f32;
+    /// Numeric type of the costs in the domain, see `Cost`
+    type Cost: Cost;
+    fn cost(&self) -> Self::Cost;
 }
 
+/// State of the search space. The plan used to reach a state, its depth
+/// and its cost are kept by the search in its `Node`, so a state only has
+/// to describe itself.
 pub trait State<A: Action> {
     fn apply_action(&self, action: &A) -> Self;
-    fn get_partial_solution(&self) -> Vec<A>;
-    fn get_solution_cost(&self) -> f32;
     fn get_applicable_actions(&self) -> Vec<A>;
     fn is_solution(&self) -> bool;
-    fn get_state_level(&self) -> usize;
+
+    /// Cost of the path used to reach the state, if the state keeps it. The
+    /// search accumulates it from `action_cost` on its own, and in debug
+    /// builds checks that both agree.
+    fn get_solution_cost(&self) -> Option<A::Cost> {
+        None
+    }
+
+    /// Cost of applying `action` in this state, for domains where it depends
+    /// on the context. By default, the context-free `Action::cost`.
+    fn action_cost(&self, action: &A) -> A::Cost {
+        action.cost()
+    }
+}
+
+pub trait StateHeuristic<A: Action> {
+    fn heuristic(&self) -> A::Cost;
+}
+
+/// State of a reversible domain, able to generate its parent states, used
+/// by the bidirectional searches
+pub trait PredecessorState<A: Action>: State<A> + Sized {
+    /// Parent states, each one with the action that turns it into this state
+    fn get_predecessors(&self) -> Vec<(Self, A)>;
+}
+
+/// Front-to-end heuristic, estimating the cost from a state to any of the
+/// two ends of a bidirectional search
+pub trait BidirectionalHeuristic<A: Action> {
+    fn heuristic_to(&self, target: &Self) -> A::Cost;
+}
+
+/// Budgets of a search, checked before each expansion. `None` means no
+/// limit.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Limits {
+    /// Maximum number of expanded states
+    pub max_nodes: Option<usize>,
+    /// Maximum wall-clock time
+    pub max_time: Option<Duration>,
+    /// Maximum number of states stored in the open and closed lists
+    pub max_stored: Option<usize>,
+}
+
+/// Limit that stopped a search
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Limit {
+    Nodes,
+    Time,
+    Memory,
 }
 
-pub trait StateHeuristic {
-    fn heuristic(&self) -> f32;
+/// Result of a run of the engine, turned into a `SearchResult` by the
+/// public searches. The runs stopped keep `P`, what was found until then.
+#[derive(Debug, Clone, PartialEq)]
+enum Outcome<S, P = ()> {
+    Solved(S),
+    /// The whole state space was explored without finding any solution
+    Exhausted,
+    LimitReached(Limit, P),
+    Cancelled(P),
 }
 
+impl<S, P> Outcome<S, P> {
+    fn map<U>(self, f: impl FnOnce(S) -> U) -> Outcome<U, P> {
+        match self {
+            Outcome::Solved(solution) => Outcome::Solved(f(solution)),
+            Outcome::Exhausted => Outcome::Exhausted,
+            Outcome::LimitReached(limit, partial) => Outcome::LimitReached(limit, partial),
+            Outcome::Cancelled(partial) => Outcome::Cancelled(partial),
+        }
+    }
+
+    /// Same outcome, keeping `partial` if the run was stopped
+    fn with_partial<Q>(self, partial: Q) -> Outcome<S, Q> {
+        match self {
+            Outcome::Solved(solution) => Outcome::Solved(solution),
+            Outcome::Exhausted => Outcome::Exhausted,
+            Outcome::LimitReached(limit, _) => Outcome::LimitReached(limit, partial),
+            Outcome::Cancelled(_) => Outcome::Cancelled(partial),
+        }
+    }
+}
+
+/// Outcome of the runs of several solutions, keeping the ones found when
+/// stopped
+type SolutionsOutcome<T, A> = Outcome<Vec<Solution<T, A>>, Vec<Solution<T, A>>>;
+
+impl<S> Outcome<Vec<S>, Vec<S>> {
+    /// Outcome of a run asked for one solution, which has no partial
+    /// solutions when stopped
+    fn first(self) -> Outcome<S> {
+        self.map(|mut solutions| solutions.swap_remove(0))
+            .with_partial(())
+    }
+}
+
+/// Snapshot of a running search, passed to the progress callback
 #[derive(Debug)]
+pub struct Progress<'a, K> {
+    pub statistics: &'a Statistics,
+    /// States in the open list
+    pub frontier: usize,
+    /// Priority of the last expanded state (`g + h` in A*), for the searches
+    /// ordered by one
+    pub best_f: Option<K>,
+}
+
+type ProgressFn<K> = Box<dyn FnMut(&Progress<K>)>;
+
+struct ProgressCallback<K> {
+    every: usize,
+    callback: ProgressFn<K>,
+}
+
+impl<K> Debug for ProgressCallback<K> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        write!(f, "ProgressCallback {{ every: {} }}", self.every)
+    }
+}
+
+/// Order used by the beam search to select the states kept in each layer
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BeamOrder {
+    /// Lowest `StateHeuristic::heuristic`
+    Heuristic,
+    /// Lowest path cost plus `StateHeuristic::heuristic`
+    CostAndHeuristic,
+}
+
+/// Solution of a suboptimal search, whose cost is at most `bound` times
+/// the cost of the optimal solution
+#[derive(Debug, Clone, PartialEq)]
+pub struct BoundedSolution<T, A: Action> {
+    pub solution: Solution<T, A>,
+    pub bound: f32,
+}
+
+/// Counters of the last run of a `Search`, reset when the next one starts
+#[derive(Debug, Clone, Default, PartialEq)]
 pub struct Statistics {
     pub nodes_explored: usize,
+    /// States created applying actions, including the duplicates pruned
+    pub nodes_generated: usize,
     pub max_depth: usize,
     pub solutions: usize,
+    pub duplicates: usize,
+    pub reopened: usize,
+    /// Peak number of states waiting in the open list
+    pub max_open: usize,
+    /// Time from the start of the run to its end, or to the last solution
+    /// yielded by the iterators
+    pub elapsed: Duration,
+    /// Expanded states, by depth
+    pub expanded_by_depth: Vec<usize>,
+    /// States discarded by the beam search, by depth of the layer
+    pub pruned_by_layer: Vec<usize>,
+}
+
+impl Statistics {
+    /// Counts the expansion of a node at `depth`
+    pub(crate) fn record_expansion(&mut self, depth: usize) {
+        self.nodes_explored += 1;
+        self.max_depth = cmp::max(self.max_depth, depth);
+        if self.expanded_by_depth.len() <= depth {
+            self.expanded_by_depth.resize(depth + 1, 0);
+        }
+        self.expanded_by_depth[depth] += 1;
+    }
+
+    /// Branching factor `b*` of the uniform tree of depth `max_depth` with
+    /// `nodes_generated` nodes below its root, 0 if nothing was generated
+    pub fn effective_branching_factor(&self) -> f64 {
+        let generated = self.nodes_generated as f64;
+        let depth = cmp::max(self.max_depth, 1) as i32;
+        let nodes = |b: f64| (1..=depth).map(|i| b.powi(i)).sum::<f64>();
+        let (mut low, mut high) = (0.0, generated);
+        for _ in 0..64 {
+            let b = (low + high) / 2.0;
+            if nodes(b) < generated {
+                low = b;
+            } else {
+                high = b;
+            }
+        }
+        high
+    }
 }
 
 impl Display for Statistics {
     fn fmt(&self, f: &mut Formatter<'_>) -> Result {
         write!(
             f,
-            "[ nodes_explored: {}, max_depth: {}, solutions: {} ]",
-            self.nodes_explored, self.max_depth, self.solutions,
+            "[ nodes_explored: {}, nodes_generated: {}, max_depth: {}, solutions: {}, \
+             duplicates: {}, reopened: {}, max_open: {}, branching_factor: {:.3}, elapsed: {:?} ]",
+            self.nodes_explored,
+            self.nodes_generated,
+            self.max_depth,
+            self.solutions,
+            self.duplicates,
+            self.reopened,
+            self.max_open,
+            self.effective_branching_factor(),
+            self.elapsed,
         )
     }
 }
 
+/// Search engine over a state space. The closed list `C` keeps the
+/// already explored states: by default a linear `VecList` that only
+/// requires `PartialEq`, or a `HashList` (see `Search::new_hashed`) for
+/// states that implement `Hash + Eq`.
 #[derive(Debug)]
-pub struct Search<T: State<A>, A: Action> {
+pub struct Search<T: State<A>, A: Action, C = VecList<Rc<T>, <A as Action>::Cost>> {
+    /// Statistics of the last run, reset when a search starts
     pub statistics: Statistics,
-    visited: Vec<Rc<T>>,
-    _marker: marker::PhantomData<A>,
+    algorithm: &'static str,
+    visited: C,
+    tie_break: TieBreak,
+    limits: Limits,
+    run_start: Instant,
+    cancel: Option<Arc<AtomicBool>>,
+    progress: Option<ProgressCallback<A::Cost>>,
+    observer: Option<BoxedObserver<T, A>>,
+    _marker: marker::PhantomData<(T, A)>,
 }
 
 impl<T, A> Default for Search<T, A>
 where
-    T: State<A> + PartialEq + Clone,
+    T: State<A> + PartialEq,
     A: Action,
 {
     fn default() -> Self {
@@ -62,187 +291,855 @@ where
 
 impl<T, A> Search<T, A>
 where
-    T: State<A> + PartialEq + Clone,
+    T: State<A> + PartialEq,
     A: Action,
 {
     pub fn new() -> Self {
+        Self::with_closed_list()
+    }
+}
+
+impl<T, A> Search<T, A, HashList<Rc<T>, A::Cost>>
+where
+    T: State<A> + Hash + Eq,
+    A: Action,
+{
+    /// Search with a hash based closed list, for states that implement
+    /// `Hash + Eq`
+    pub fn new_hashed() -> Self {
+        Self::with_closed_list()
+    }
+}
+
+impl<T, A, C> Search<T, A, C>
+where
+    T: State<A>,
+    A: Action,
+    C: ClosedList<Rc<T>, A::Cost>,
+{
+    pub fn with_closed_list() -> Self {
         Search {
-            statistics: Statistics {
-                nodes_explored: 0,
-                max_depth: 0,
-                solutions: 0,
-            },
-            visited: Vec::new(),
-            _marker: std::marker::PhantomData::default(),
+            statistics: Statistics::default(),
+            algorithm: "none",
+            visited: C::new(),
+            tie_break: TieBreak::Lifo,
+            limits: Limits::default(),
+            run_start: Instant::now(),
+            cancel: None,
+            progress: None,
+            observer: None,
+            _marker: marker::PhantomData,
         }
     }
 
-    pub fn search_breadth_all(&mut self, initial_state: T) -> Vec<Vec<A>> {
-        self.search_breadth(initial_state, true)
+    /// Flag polled before each expansion by the same searches that respect
+    /// the `Limits`: once raised, they stop with `SearchError::Cancelled`
+    pub fn set_cancel_flag(&mut self, cancel: Arc<AtomicBool>) {
+        self.cancel = Some(cancel);
     }
 
-    pub fn search_breadth_first(&mut self, initial_state: T) -> Option<Vec<A>> {
-        self.search_breadth(initial_state, false).pop()
+    /// Callback called every `every` expanded states by the same searches
+    /// that respect the `Limits`
+    ///
+    /// # Panics
+    ///
+    /// Panics if `every` is 0.
+    pub fn set_progress(
+        &mut self,
+        every: usize,
+        callback: impl FnMut(&Progress<A::Cost>) + 'static,
+    ) {
+        assert!(every > 0, "progress requires an interval > 0");
+        self.progress = Some(ProgressCallback {
+            every,
+            callback: Box::new(callback),
+        });
     }
 
-    pub fn search_depth_all(&mut self, initial_state: T) -> Vec<Vec<A>> {
-        self.search_depth(initial_state, true, 0)
+    /// Budgets for all the searches. The memory limit counts the states in
+    /// the open and closed lists, or the current path in IDA*.
+    pub fn set_limits(&mut self, limits: Limits) {
+        self.limits = limits;
     }
 
-    pub fn search_depth_first(&mut self, initial_state: T) -> Option<Vec<A>> {
-        self.search_depth(initial_state, false, 0).pop()
+    /// Order used by the informed searches for states with the same weight
+    pub fn set_tie_break(&mut self, tie_break: TieBreak) {
+        self.tie_break = tie_break;
     }
 
+    /// Observer called by every search on each expansion, generation,
+    /// duplicate, cutoff and solution
+    pub fn set_observer(&mut self, observer: impl Observer<T, A> + 'static) {
+        self.observer = Some(BoxedObserver::new(observer));
+    }
+
+    pub fn remove_observer(&mut self) {
+        self.observer = None;
+    }
+
+    /// Statistics of the last run, with the algorithm and limits used
+    pub fn report(&self) -> Report<'_> {
+        Report {
+            algorithm: self.algorithm,
+            limits: self.limits,
+            statistics: &self.statistics,
+        }
+    }
+}
+
+impl<T, A, C> Search<T, A, C>
+where
+    T: State<A> + Clone,
+    A: Action + Clone,
+    C: ClosedList<Rc<T>, A::Cost>,
+{
+    pub fn search_breadth_all(&mut self, initial_state: T) -> SolutionsResult<T, A> {
+        self.run_all("breadth_first", |search| {
+            search.search_breadth(initial_state, 0)
+        })
+    }
+
+    pub fn search_breadth_first(
+        &mut self,
+        initial_state: T,
+    ) -> SearchResult<Option<Solution<T, A>>> {
+        self.run("breadth_first", |search| {
+            search.search_breadth(initial_state, 1).first()
+        })
+    }
+
+    pub fn search_depth_all(&mut self, initial_state: T) -> SolutionsResult<T, A> {
+        self.run_all("depth_first", |search| {
+            search.search_depth(initial_state, 0, 0)
+        })
+    }
+
+    pub fn search_depth_first(&mut self, initial_state: T) -> SearchResult<Option<Solution<T, A>>> {
+        self.run("depth_first", |search| {
+            search.search_depth(initial_state, 1, 0).first()
+        })
+    }
+
+    /// Lazy version of `search_breadth_all`, see `Solutions`
+    pub fn iter_breadth(&mut self, initial_state: T) -> BreadthSolutions<'_, T, A, C> {
+        self.start_run("breadth_first");
+        Solutions::new(self, initial_state)
+    }
+
+    /// Lazy version of `search_depth_all`, see `Solutions`
+    pub fn iter_depth(&mut self, initial_state: T) -> DepthSolutions<'_, T, A, C> {
+        self.start_run("depth_first");
+        Solutions::new(self, initial_state)
+    }
+
+    /// Depth first passes with a depth limit raised by `limit_step`, which
+    /// must be greater than 0, after each pass without solutions
     pub fn search_iter_depth_first(
         &mut self,
         initial_state: T,
         limit_step: usize,
-    ) -> Option<Vec<A>> {
-        let mut limit = limit_step;
-        loop {
-            let solution = self.search_depth(initial_state.clone(), false, limit).pop();
-            match solution {
-                Some(solution) => return Some(solution),
-                None => {
-                    if limit > self.statistics.max_depth {
-                        return None;
+    ) -> SearchResult<Option<Solution<T, A>>> {
+        let algorithm = "iterative_deepening";
+        self.check_parameter(algorithm, limit_step > 0, "the limit step must be > 0")?;
+        self.run(algorithm, |search| {
+            let mut limit = limit_step;
+            loop {
+                match search.search_depth(initial_state.clone(), 1, limit) {
+                    Outcome::Exhausted => {
+                        if limit > search.statistics.max_depth {
+                            return Outcome::Exhausted;
+                        }
+                        limit += limit_step;
+                        search.visited.clear();
                     }
-                    limit += limit_step;
-                    self.visited.clear();
+                    outcome => return outcome.first(),
                 }
             }
-        }
+        })
+    }
+
+    /// All the solutions, ordered by the sum of their `State::action_cost`.
+    /// As in `search_a_star_all`, only goal states are reached by more than
+    /// one plan.
+    pub fn search_uniform_cost_all(&mut self, initial_state: T) -> SolutionsResult<T, A> {
+        self.run_all("uniform_cost", |search| {
+            search.search_uniform_cost(initial_state, 0)
+        })
+    }
+
+    /// Cheapest solution by the sum of its `State::action_cost`
+    pub fn search_uniform_cost_first(
+        &mut self,
+        initial_state: T,
+    ) -> SearchResult<Option<Solution<T, A>>> {
+        self.run("uniform_cost", |search| {
+            search.search_uniform_cost(initial_state, 1).first()
+        })
     }
 }
 
-impl<T, A> Search<T, A>
+impl<T, A, C> Search<T, A, C>
 where
-    T: State<A> + StateHeuristic + PartialEq,
-    A: Action,
+    T: State<A> + StateHeuristic<A>,
+    A: Action + Clone,
+    C: ClosedList<Rc<T>, A::Cost>,
 {
-    pub fn search_a_start_first(&mut self, initial_state: T) -> Option<Vec<A>> {
-        let mut open_list: PrioList<Rc<T>> = PrioList::new();
-        self.find_solutions_a_start(initial_state, &mut open_list, false, 0)
-            .pop()
+    pub fn search_a_start_first(
+        &mut self,
+        initial_state: T,
+    ) -> SearchResult<Option<Solution<T, A>>> {
+        self.run("a_star", |search| {
+            search.search_a_star(initial_state, 1).first()
+        })
+    }
+
+    /// All the solutions, in non-decreasing cost order when the heuristic
+    /// is consistent. Goal states are never closed, so a goal reached from
+    /// different states gives a plan for each, but the other states are
+    /// expanded once: use `search_a_star_k_best` for the cheapest plans
+    /// through the same states.
+    pub fn search_a_star_all(&mut self, initial_state: T) -> SolutionsResult<T, A> {
+        self.run_all("a_star", |search| search.search_a_star(initial_state, 0))
+    }
+
+    /// The `k` cheapest plans, in non-decreasing cost order when the
+    /// heuristic is consistent. Each state can be expanded up to `k` times,
+    /// so different plans can go through the same states.
+    pub fn search_a_star_k_best(&mut self, initial_state: T, k: usize) -> SolutionsResult<T, A> {
+        self.run_all("a_star", |search| match k {
+            0 => Outcome::Solved(Vec::new()),
+            k => search.search_a_star(initial_state, k),
+        })
+    }
+
+    /// Weighted A*: states ordered by `g + weight * h`. With an admissible
+    /// heuristic, the solution cost is within `weight` times the optimal.
+    /// `weight` must be at least 1.
+    pub fn search_weighted_a_star_first(
+        &mut self,
+        initial_state: T,
+        weight: f32,
+    ) -> SearchResult<Option<BoundedSolution<T, A>>> {
+        let algorithm = "weighted_a_star";
+        self.check_parameter(algorithm, weight >= 1.0, "the weight must be >= 1")?;
+        self.run(algorithm, |search| {
+            let mut open_list: PrioList<Rc<Node<T, A>>, A::Cost> =
+                PrioList::with_tie_break(search.tie_break);
+            search
+                .find_solutions_a_start(
+                    initial_state,
+                    &mut open_list,
+                    |n| {
+                        let h = n.state().heuristic();
+                        (n.cost() + h.scale(weight), Some(h))
+                    },
+                    true,
+                    1,
+                    0,
+                )
+                .first()
+                .map(|solution| BoundedSolution {
+                    solution,
+                    bound: weight,
+                })
+        })
+    }
+
+    /// IDA*: repeated depth-first passes bounded by `g + h`, where each pass
+    /// raises the bound to the lowest `g + h` that exceeded the previous one.
+    /// Only the current path is kept in memory, checked to avoid cycles.
+    pub fn search_ida_star_first(
+        &mut self,
+        initial_state: T,
+    ) -> SearchResult<Option<Solution<T, A>>>
+    where
+        T: PartialEq,
+    {
+        self.run("ida_star", |search| {
+            let root: Rc<Node<T, A>> = Rc::new(Node::root(initial_state));
+            let mut threshold = root.cost() + root.state().heuristic();
+            loop {
+                match search.ida_star_pass(&root, threshold) {
+                    IdaPass::Found(solution) => return Outcome::Solved(solution),
+                    IdaPass::Exceeded(next) if next < A::Cost::MAX => threshold = next,
+                    IdaPass::Exceeded(_) => return Outcome::Exhausted,
+                    IdaPass::Interrupted(outcome) => return outcome,
+                }
+            }
+        })
+    }
+
+    /// Beam search: expands level by level like `search_breadth_first`, but
+    /// keeps only the best `width` states of each layer by `order`. `width`
+    /// must be greater than 0.
+    pub fn search_beam_first(
+        &mut self,
+        initial_state: T,
+        width: usize,
+        order: BeamOrder,
+    ) -> SearchResult<Option<Solution<T, A>>> {
+        let algorithm = "beam";
+        self.check_parameter(algorithm, width > 0, "the width must be > 0")?;
+        self.run(algorithm, |search| match order {
+            BeamOrder::Heuristic => {
+                search.find_solution_beam(initial_state, width, |n| n.state().heuristic())
+            }
+            BeamOrder::CostAndHeuristic => search
+                .find_solution_beam(initial_state, width, |n| n.cost() + n.state().heuristic()),
+        })
+    }
+
+    /// First solution found expanding the states with the lowest
+    /// `StateHeuristic::heuristic`, ignoring the cost to reach them
+    pub fn search_greedy_first(
+        &mut self,
+        initial_state: T,
+    ) -> SearchResult<Option<Solution<T, A>>> {
+        self.run("greedy", |search| {
+            let mut open_list: PrioList<Rc<Node<T, A>>, A::Cost> =
+                PrioList::with_tie_break(search.tie_break);
+            search
+                .find_solutions_a_start(
+                    initial_state,
+                    &mut open_list,
+                    |n| {
+                        let h = n.state().heuristic();
+                        (h, Some(h))
+                    },
+                    false,
+                    1,
+                    0,
+                )
+                .first()
+        })
+    }
+
+    fn search_a_star(&mut self, initial_state: T, max_solutions: usize) -> SolutionsOutcome<T, A> {
+        let mut open_list: PrioList<Rc<Node<T, A>>, A::Cost> =
+            PrioList::with_tie_break(self.tie_break);
+        self.find_solutions_a_start(
+            initial_state,
+            &mut open_list,
+            |n| {
+                let h = n.state().heuristic();
+                (n.cost() + h, Some(h))
+            },
+            true,
+            max_solutions,
+            0,
+        )
     }
 }
 
-impl<T, A> Search<T, A>
+impl<T, A, C> Search<T, A, C>
 where
-    T: State<A> + PartialEq,
-    A: Action,
+    T: State<A>,
+    A: Action + Clone,
+    C: ClosedList<Rc<T>, A::Cost>,
 {
-    fn search_breadth(&mut self, initial_state: T, all_solutions: bool) -> Vec<Vec<A>> {
-        let mut open_list: Queue<Rc<T>> = Queue::new();
-        self.find_solutions(initial_state, &mut open_list, all_solutions, 0)
+    /// Up to `max_solutions` solutions, 0 for all, expanding the nodes in
+    /// the order given by `open_list`: a `Queue` searches breadth first and
+    /// a `Stack` depth first. The open list is cleared before starting.
+    pub fn search_with_frontier(
+        &mut self,
+        initial_state: T,
+        open_list: &mut impl OpenList<Rc<Node<T, A>>>,
+        max_solutions: usize,
+    ) -> SolutionsResult<T, A> {
+        self.run_all("custom_frontier", |search| {
+            search.find_solutions(initial_state, open_list, max_solutions, 0)
+        })
+    }
+
+    fn search_breadth(&mut self, initial_state: T, max_solutions: usize) -> SolutionsOutcome<T, A> {
+        let mut open_list: Queue<Rc<Node<T, A>>> = Queue::new();
+        self.find_solutions(initial_state, &mut open_list, max_solutions, 0)
     }
 
-    fn search_depth(&mut self, initial_state: T, all_solutions: bool, limit: usize) -> Vec<Vec<A>> {
-        let mut open_list: Stack<Rc<T>> = Stack::new();
-        self.find_solutions(initial_state, &mut open_list, all_solutions, limit)
+    fn search_depth(
+        &mut self,
+        initial_state: T,
+        max_solutions: usize,
+        limit: usize,
+    ) -> SolutionsOutcome<T, A> {
+        let mut open_list: Stack<Rc<Node<T, A>>> = Stack::new();
+        self.find_solutions(initial_state, &mut open_list, max_solutions, limit)
+    }
+
+    fn search_uniform_cost(
+        &mut self,
+        initial_state: T,
+        max_solutions: usize,
+    ) -> SolutionsOutcome<T, A> {
+        let mut open_list: PrioList<Rc<Node<T, A>>, A::Cost> =
+            PrioList::with_tie_break(self.tie_break);
+        self.find_solutions_a_start(
+            initial_state,
+            &mut open_list,
+            |n| (n.cost(), None),
+            true,
+            max_solutions,
+            0,
+        )
     }
 
+    // `max_solutions` and `limit` set to 0 mean no bound
     fn find_solutions(
         &mut self,
         state: T,
-        open_list: &mut impl OpenList<Rc<T>>,
-        all_solutions: bool,
+        open_list: &mut impl OpenList<Rc<Node<T, A>>>,
+        max_solutions: usize,
         limit: usize,
-    ) -> Vec<Vec<A>> {
-        let mut max_level: usize = 0;
-        let mut solutions: Vec<Vec<A>> = Vec::new();
+    ) -> SolutionsOutcome<T, A> {
+        let mut solutions: Vec<Solution<T, A>> = Vec::new();
 
         open_list.clear();
-        open_list.add(Rc::new(state));
+        open_list.add(Rc::new(Node::root(state)));
+        loop {
+            match self.next_solution(open_list, limit) {
+                Outcome::Solved(solution) => {
+                    solutions.push(solution);
+                    if solutions.len() == max_solutions {
+                        return Outcome::Solved(solutions);
+                    }
+                }
+                Outcome::Exhausted if !solutions.is_empty() => return Outcome::Solved(solutions),
+                outcome => {
+                    return outcome
+                        .map(|solution| vec![solution])
+                        .with_partial(solutions)
+                }
+            }
+        }
+    }
+
+    /// Resumes the search until the next solution, leaving the open and
+    /// closed lists ready to continue after it
+    fn next_solution(
+        &mut self,
+        open_list: &mut impl OpenList<Rc<Node<T, A>>>,
+        limit: usize,
+    ) -> Outcome<Solution<T, A>> {
         while !open_list.is_empty() {
+            self.record_frontier(open_list.len());
+            if let Some(outcome) = self.interruption(open_list.len()) {
+                return outcome;
+            }
             match open_list.get() {
-                None => return solutions,
-                Some(current_state) if !self.visited.contains(&current_state) => {
-                    self.visited.push(Rc::clone(&current_state));
-                    max_level = cmp::max(max_level, current_state.get_state_level());
-                    self.statistics.nodes_explored += 1;
-                    self.statistics.max_depth = cmp::max(self.statistics.max_depth, max_level);
-
-                    if current_state.is_solution() {
+                None => break,
+                Some(current) if self.visited.add(Rc::clone(current.state())) => {
+                    let level = current.depth();
+                    self.record_expansion(level);
+                    self.notify(Hook::Expanded, &current, || None);
+                    self.report_progress(open_list.len(), None);
+
+                    if current.state().is_solution() {
                         self.statistics.solutions += 1;
-                        solutions.push(current_state.get_partial_solution());
-                        if !all_solutions {
-                            return solutions;
-                        }
-                        continue;
+                        self.notify(Hook::Solution, &current, || None);
+                        return Outcome::Solved(Solution::from_node(
+                            &current,
+                            self.statistics.nodes_explored,
+                        ));
                     }
 
                     // expand
-                    if (limit > 0 && current_state.get_state_level() < limit) || (limit < 1) {
-                        current_state
-                            .get_applicable_actions()
-                            .iter()
-                            .map(|a| Rc::new(current_state.apply_action(a)))
-                            .filter(|s| !self.visited.contains(s))
-                            .for_each(|s| open_list.add(s));
+                    if limit > 0 && level >= limit {
+                        self.notify(Hook::DepthCutoff, &current, || None);
+                        continue;
+                    }
+                    for n in current.children() {
+                        self.statistics.nodes_generated += 1;
+                        self.notify(Hook::Generated, &n, || None);
+                        if self.visited.contains(n.state()) {
+                            self.statistics.duplicates += 1;
+                            self.notify(Hook::Duplicate, &n, || None);
+                            continue;
+                        }
+                        open_list.add(n);
                     }
                 }
                 // to ignore visited
-                _ => continue,
+                Some(current) => {
+                    self.statistics.duplicates += 1;
+                    self.notify(Hook::Duplicate, &current, || None);
+                }
             };
         }
-        solutions
+        Outcome::Exhausted
     }
-}
 
-impl<T, A> Search<T, A>
-where
-    T: State<A> + StateHeuristic + PartialEq,
-    A: Action,
-{
+    /// Best first search, `evaluate` gives the priority of a node in the
+    /// open list and its heuristic value, if any. Goal states are never
+    /// closed, and the other states are expanded up to `max_solutions`
+    /// times, so the k best plans can share states.
     fn find_solutions_a_start(
         &mut self,
         state: T,
-        open_list: &mut impl PriorityOpenList<Rc<T>>,
-        all_solutions: bool,
+        open_list: &mut impl PriorityOpenList<Rc<Node<T, A>>, A::Cost>,
+        evaluate: impl Fn(&Node<T, A>) -> (A::Cost, Option<A::Cost>),
+        reopen: bool,
+        max_solutions: usize,
         limit: usize,
-    ) -> Vec<Vec<A>> {
-        let mut max_level: usize = 0;
-        let mut solutions: Vec<Vec<A>> = Vec::new();
+    ) -> SolutionsOutcome<T, A> {
+        let mut solutions: Vec<Solution<T, A>> = Vec::new();
+        // closed lists of the second and later expansions of the states
+        let mut expansions: Vec<C> = (1..max_solutions).map(|_| C::new()).collect();
 
         open_list.clear();
-        let weight = state.get_solution_cost() + state.heuristic();
-        open_list.add(Rc::new(state), weight);
+        let root = Rc::new(Node::root(state));
+        let (initial_weight, _) = evaluate(&root);
+        open_list.add(root, initial_weight);
         while !open_list.is_empty() {
+            self.record_frontier(open_list.len());
+            if let Some(outcome) = self.interruption(open_list.len()) {
+                return outcome.with_partial(solutions);
+            }
             match open_list.get() {
-                None => return solutions,
-                Some(current_state) if !self.visited.contains(&current_state) => {
-                    self.visited.push(Rc::clone(&current_state));
-                    max_level = cmp::max(max_level, current_state.get_state_level());
-                    self.statistics.nodes_explored += 1;
-                    self.statistics.max_depth = cmp::max(self.statistics.max_depth, max_level);
-
-                    if current_state.is_solution() {
+                None => break,
+                Some(current)
+                    if current.state().is_solution()
+                        || self.close_state(&current, reopen)
+                        || expansions
+                            .iter_mut()
+                            .any(|closed| closed.add(Rc::clone(current.state()))) =>
+                {
+                    let (f, h) = evaluate(&current);
+                    self.record_expansion(current.depth());
+                    self.notify(Hook::Expanded, &current, || h);
+                    self.report_progress(open_list.len(), Some(f));
+
+                    if current.state().is_solution() {
                         self.statistics.solutions += 1;
-                        solutions.push(current_state.get_partial_solution());
-                        if !all_solutions {
-                            return solutions;
+                        self.notify(Hook::Solution, &current, || h);
+                        solutions.push(Solution::from_node(
+                            &current,
+                            self.statistics.nodes_explored,
+                        ));
+                        if solutions.len() == max_solutions {
+                            return Outcome::Solved(solutions);
                         }
                         continue;
                     }
 
                     // expand
-                    if (limit > 0 && current_state.get_state_level() < limit) || (limit < 1) {
-                        current_state
-                            .get_applicable_actions()
-                            .iter()
-                            .map(|a| Rc::new(current_state.apply_action(a)))
-                            .filter(|s| !self.visited.contains(s))
-                            .for_each(|s| {
-                                let weight = s.get_solution_cost() + s.heuristic();
-                                open_list.add(s, weight);
-                            });
+                    if limit > 0 && current.depth() >= limit {
+                        self.notify(Hook::DepthCutoff, &current, || h);
+                        continue;
+                    }
+                    for n in current.children() {
+                        self.statistics.nodes_generated += 1;
+                        let (weight, h) = evaluate(&n);
+                        self.notify(Hook::Generated, &n, || h);
+                        let duplicate =
+                            match self.visited.get_cost(n.state()) {
+                                Some(cost) => !reopen || cost <= n.cost(),
+                                None => false,
+                            } && expansions.iter().all(|closed| closed.contains(n.state()));
+                        if duplicate {
+                            self.statistics.duplicates += 1;
+                            self.notify(Hook::Duplicate, &n, || h);
+                            continue;
+                        }
+                        open_list.add(n, weight);
                     }
                 }
                 // to ignore visited
-                _ => continue,
+                Some(current) => {
+                    self.statistics.duplicates += 1;
+                    self.notify(Hook::Duplicate, &current, || evaluate(&current).1);
+                }
             };
         }
-        solutions
+        match solutions.is_empty() {
+            true => Outcome::Exhausted,
+            false => Outcome::Solved(solutions),
+        }
+    }
+
+    /// Calls the observer, if any, computing the heuristic value only then
+    fn notify(&mut self, hook: Hook, node: &Node<T, A>, h: impl FnOnce() -> Option<A::Cost>) {
+        if let Some(observer) = &mut self.observer {
+            observer.call(hook, &Event::from_node(node, h()));
+        }
+    }
+
+    /// Fails with `SearchError::InvalidParameter` unless `valid`, resetting
+    /// the statistics first, so they do not describe the previous run
+    fn check_parameter(
+        &mut self,
+        algorithm: &'static str,
+        valid: bool,
+        message: &str,
+    ) -> SearchResult<()> {
+        if !valid {
+            self.start_run(algorithm);
+        }
+        error::check_parameter(algorithm, valid, message)
+    }
+
+    fn start_run(&mut self, algorithm: &'static str) {
+        self.algorithm = algorithm;
+        self.visited.clear();
+        self.statistics = Statistics::default();
+        self.run_start = Instant::now();
+    }
+
+    /// Starts a run of `algorithm` with `search`, turning its outcome into
+    /// the result of the public searches. A panic of the domain stops the
+    /// run with `SearchError::Panicked`.
+    fn run<S, P>(
+        &mut self,
+        algorithm: &'static str,
+        search: impl FnOnce(&mut Self) -> Outcome<S, P>,
+    ) -> SearchResult<Option<S>, P> {
+        self.start_run(algorithm);
+        let result = match panic::catch_unwind(AssertUnwindSafe(|| search(self))) {
+            Ok(outcome) => outcome.into_result(algorithm),
+            Err(payload) => Err(SearchError::panicked(algorithm, payload)),
+        };
+        self.statistics.elapsed = self.run_start.elapsed();
+        result
+    }
+
+    /// `run` for the searches of several solutions, with no solutions when
+    /// the state space is exhausted
+    fn run_all<S>(
+        &mut self,
+        algorithm: &'static str,
+        search: impl FnOnce(&mut Self) -> Outcome<Vec<S>, Vec<S>>,
+    ) -> SearchResult<Vec<S>, Vec<S>> {
+        self.run(algorithm, search).map(Option::unwrap_or_default)
+    }
+
+    fn record_expansion(&mut self, depth: usize) {
+        self.statistics.record_expansion(depth);
+    }
+
+    fn record_frontier(&mut self, open_states: usize) {
+        self.statistics.max_open = cmp::max(self.statistics.max_open, open_states);
+    }
+
+    fn is_cancelled(&self) -> bool {
+        self.cancel
+            .as_ref()
+            .is_some_and(|cancel| cancel.load(Ordering::Relaxed))
+    }
+
+    fn report_progress(&mut self, frontier: usize, best_f: Option<A::Cost>) {
+        if let Some(progress) = &mut self.progress {
+            if self
+                .statistics
+                .nodes_explored
+                .is_multiple_of(progress.every)
+            {
+                self.statistics.elapsed = self.run_start.elapsed();
+                (progress.callback)(&Progress {
+                    statistics: &self.statistics,
+                    frontier,
+                    best_f,
+                });
+            }
+        }
+    }
+
+    /// Outcome stopping the run before the next expansion, if the search
+    /// was cancelled or reached one of its limits
+    fn interruption<S>(&self, open_states: usize) -> Option<Outcome<S>> {
+        if self.is_cancelled() {
+            return Some(Outcome::Cancelled(()));
+        }
+        self.limit_reached(open_states)
+            .map(|limit| Outcome::LimitReached(limit, ()))
+    }
+
+    fn limit_reached(&self, open_states: usize) -> Option<Limit> {
+        let limits = &self.limits;
+        if limits
+            .max_nodes
+            .is_some_and(|max| self.statistics.nodes_explored >= max)
+        {
+            return Some(Limit::Nodes);
+        }
+        if limits
+            .max_stored
+            .is_some_and(|max| open_states + self.visited.len() > max)
+        {
+            return Some(Limit::Memory);
+        }
+        if limits
+            .max_time
+            .is_some_and(|max| self.run_start.elapsed() >= max)
+        {
+            return Some(Limit::Time);
+        }
+        None
+    }
+
+    /// Adds the state to the closed list, returning `false` if it was
+    /// already there. With `reopen`, a state already closed is reopened
+    /// when it is reached again with a lower cost.
+    fn close_state(&mut self, node: &Node<T, A>, reopen: bool) -> bool {
+        if !reopen {
+            return self.visited.add(Rc::clone(node.state()));
+        }
+        match self.visited.get_cost(node.state()) {
+            Some(closed_cost) if closed_cost <= node.cost() => return false,
+            Some(_) => self.statistics.reopened += 1,
+            None => (),
+        }
+        self.visited.set_cost(Rc::clone(node.state()), node.cost());
+        true
+    }
+}
+
+impl<T, A, C> Search<T, A, C>
+where
+    T: State<A> + StateHeuristic<A>,
+    A: Action + Clone,
+    C: ClosedList<Rc<T>, A::Cost>,
+{
+    fn find_solution_beam(
+        &mut self,
+        state: T,
+        width: usize,
+        weight: impl Fn(&Node<T, A>) -> A::Cost,
+    ) -> Outcome<Solution<T, A>> {
+        let root = Rc::new(Node::root(state));
+        self.visited.add(Rc::clone(root.state()));
+        let mut layer = vec![root];
+        let mut depth: usize = 0;
+
+        while !layer.is_empty() {
+            let mut candidates = Vec::new();
+            let mut open_states = layer.len();
+            for current in layer {
+                if let Some(outcome) = self.interruption(open_states + candidates.len()) {
+                    return outcome;
+                }
+                open_states -= 1;
+                let h = || Some(current.state().heuristic());
+                self.record_expansion(depth);
+                self.notify(Hook::Expanded, &current, h);
+                self.report_progress(open_states + candidates.len(), None);
+
+                if current.state().is_solution() {
+                    self.statistics.solutions += 1;
+                    self.notify(Hook::Solution, &current, h);
+                    return Outcome::Solved(Solution::from_node(
+                        &current,
+                        self.statistics.nodes_explored,
+                    ));
+                }
+
+                // expand
+                for n in current.children() {
+                    let h = || Some(n.state().heuristic());
+                    self.statistics.nodes_generated += 1;
+                    self.notify(Hook::Generated, &n, h);
+                    if self.visited.contains(n.state()) {
+                        self.statistics.duplicates += 1;
+                        self.notify(Hook::Duplicate, &n, h);
+                        continue;
+                    }
+                    candidates.push((weight(&n), n));
+                }
+            }
+
+            if candidates.is_empty() {
+                break;
+            }
+            self.record_frontier(candidates.len());
+
+            // keep the best `width` states of the next layer
+            depth += 1;
+            candidates.sort_by(|(w1, _), (w2, _)| w1.total_cmp(w2));
+            layer = Vec::new();
+            let mut pruned: usize = 0;
+            for (_, n) in candidates {
+                if layer.len() == width {
+                    pruned += 1;
+                } else if self.visited.add(Rc::clone(n.state())) {
+                    layer.push(n);
+                } else {
+                    self.statistics.duplicates += 1;
+                    self.notify(Hook::Duplicate, &n, || Some(n.state().heuristic()));
+                }
+            }
+            if self.statistics.pruned_by_layer.len() <= depth {
+                self.statistics.pruned_by_layer.resize(depth + 1, 0);
+            }
+            self.statistics.pruned_by_layer[depth] += pruned;
+        }
+        Outcome::Exhausted
+    }
+}
+
+enum IdaPass<T, A: Action> {
+    Found(Solution<T, A>),
+    /// lowest `g + h` over the threshold, `Cost::MAX` if no state exceeded it
+    Exceeded(A::Cost),
+    /// cancelled or stopped at a limit
+    Interrupted(Outcome<Solution<T, A>>),
+}
+
+impl<T, A, C> Search<T, A, C>
+where
+    T: State<A> + StateHeuristic<A> + PartialEq,
+    A: Action + Clone,
+    C: ClosedList<Rc<T>, A::Cost>,
+{
+    fn ida_star_pass(&mut self, current: &Rc<Node<T, A>>, threshold: A::Cost) -> IdaPass<T, A> {
+        let h = current.state().heuristic();
+        let f = current.cost() + h;
+        if f > threshold {
+            self.notify(Hook::DepthCutoff, current, || Some(h));
+            return IdaPass::Exceeded(f);
+        }
+
+        // the open list of IDA* is the current path
+        let open_states = current.depth() + 1;
+        if let Some(outcome) = self.interruption(open_states) {
+            return IdaPass::Interrupted(outcome);
+        }
+        self.record_expansion(current.depth());
+        self.notify(Hook::Expanded, current, || Some(h));
+        self.record_frontier(open_states);
+        self.report_progress(open_states, Some(f));
+
+        if current.state().is_solution() {
+            self.statistics.solutions += 1;
+            self.notify(Hook::Solution, current, || Some(h));
+            return IdaPass::Found(Solution::from_node(current, self.statistics.nodes_explored));
+        }
+
+        // expand
+        let mut next_threshold = A::Cost::MAX;
+        for child in current.children() {
+            self.statistics.nodes_generated += 1;
+            self.notify(Hook::Generated, &child, || Some(child.state().heuristic()));
+            // to ignore cycles in the current path
+            if on_path(current, child.state()) {
+                self.statistics.duplicates += 1;
+                self.notify(Hook::Duplicate, &child, || Some(child.state().heuristic()));
+                continue;
+            }
+            match self.ida_star_pass(&child, threshold) {
+                IdaPass::Exceeded(f) => next_threshold = next_threshold.min_cost(f),
+                pass => return pass,
+            }
+        }
+        IdaPass::Exceeded(next_threshold)
+    }
+}
+
+/// Whether `state` is in `node` or any of its ancestors
+fn on_path<T: PartialEq, A: Action>(node: &Node<T, A>, state: &T) -> bool {
+    let mut node = Some(node);
+    while let Some(current) = node {
+        if current.state().as_ref() == state {
+            return true;
+        }
+        node = current.parent().map(|parent| parent.as_ref());
     }
+    false
 }
@@ -0,0 +1,438 @@
+use std::collections::HashMap;
+use std::hash::Hash;
+use std::marker::PhantomData;
+use std::mem;
+use std::rc::Rc;
+
+use super::observer::{Event, Hook};
+use super::{
+    Action, BidirectionalHeuristic, Outcome, PredecessorState, Search, SearchResult, Solution,
+};
+use crate::closedlist::ClosedList;
+use crate::cost::Cost;
+use crate::openlist::prio_list::PrioList;
+use crate::openlist::PriorityOpenList;
+
+/// Link of a state to its neighbour in the path to the end where its search
+/// started: the parent state in the forward search and the child state in
+/// the backward one, with the action between both
+struct Link<T, A: Action> {
+    next: Option<(Rc<T>, A)>,
+    cost: A::Cost,
+    depth: usize,
+    closed: bool,
+}
+
+impl<T, A: Action> Link<T, A> {
+    fn root() -> Self {
+        Link {
+            next: None,
+            cost: A::Cost::ZERO,
+            depth: 0,
+            closed: false,
+        }
+    }
+}
+
+type Links<T, A> = HashMap<Rc<T>, Link<T, A>>;
+
+/// Open list of the bidirectional heuristic search, with `(priority, g,
+/// state)` entries
+type Frontier<T, A> =
+    PrioList<(<A as Action>::Cost, <A as Action>::Cost, Rc<T>), <A as Action>::Cost>;
+
+/// One of the two searches of the bidirectional heuristic search
+struct Side<T, A: Action> {
+    forward: bool,
+    target: Rc<T>,
+    links: Links<T, A>,
+    open: Frontier<T, A>,
+}
+
+impl<T, A: Action> Side<T, A> {
+    fn min_priority(&self) -> Option<A::Cost> {
+        self.open.peek().map(|(priority, _, _)| *priority)
+    }
+}
+
+impl<T, A, C> Search<T, A, C>
+where
+    T: PredecessorState<A> + Hash + Eq,
+    A: Action + Clone,
+    C: ClosedList<Rc<T>, A::Cost>,
+{
+    /// Shortest plan in number of actions from `initial_state` to
+    /// `goal_state`, expanding by layers the smaller of the forward and the
+    /// backward frontiers until they meet
+    pub fn search_bidirectional_breadth_first(
+        &mut self,
+        initial_state: T,
+        goal_state: T,
+    ) -> SearchResult<Option<Solution<T, A>>> {
+        self.run("bidirectional_breadth_first", |search| {
+            search.bidirectional_breadth_first(initial_state, goal_state)
+        })
+    }
+
+    /// Cheapest plan from `initial_state` to `goal_state`, with two
+    /// heuristic searches (MM) that expand states by `max(g + h, 2g)` and
+    /// stop when no better meeting point than the best found can remain
+    pub fn search_bidirectional_heuristic_first(
+        &mut self,
+        initial_state: T,
+        goal_state: T,
+    ) -> SearchResult<Option<Solution<T, A>>>
+    where
+        T: BidirectionalHeuristic<A>,
+    {
+        self.run("bidirectional_heuristic", |search| {
+            search.bidirectional_heuristic_first(initial_state, goal_state)
+        })
+    }
+
+    fn bidirectional_breadth_first(
+        &mut self,
+        initial_state: T,
+        goal_state: T,
+    ) -> Outcome<Solution<T, A>> {
+        if initial_state == goal_state {
+            self.statistics.solutions += 1;
+            self.notify_link(Hook::Solution, &initial_state, &Link::root(), || None);
+            return Outcome::Solved(self.empty_solution(initial_state));
+        }
+
+        let initial_state = Rc::new(initial_state);
+        let goal_state = Rc::new(goal_state);
+        let mut forward: Links<T, A> = HashMap::new();
+        let mut backward: Links<T, A> = HashMap::new();
+        forward.insert(Rc::clone(&initial_state), Link::root());
+        backward.insert(Rc::clone(&goal_state), Link::root());
+        let mut forward_layer = vec![initial_state];
+        let mut backward_layer = vec![goal_state];
+
+        while !forward_layer.is_empty() && !backward_layer.is_empty() {
+            self.record_frontier(forward_layer.len() + backward_layer.len());
+            let expanded = if forward_layer.len() <= backward_layer.len() {
+                self.expand_layer(&mut forward_layer, &mut forward, &backward, true)
+            } else {
+                self.expand_layer(&mut backward_layer, &mut backward, &forward, false)
+            };
+            let meeting = match expanded {
+                Ok(meeting) => meeting,
+                Err(outcome) => return outcome,
+            };
+            if let Some(meeting) = meeting {
+                self.statistics.solutions += 1;
+                self.notify_link(Hook::Solution, &meeting, &forward[&meeting], || None);
+                return Outcome::Solved(self.splice(&meeting, &forward, &backward));
+            }
+        }
+        Outcome::Exhausted
+    }
+
+    fn bidirectional_heuristic_first(
+        &mut self,
+        initial_state: T,
+        goal_state: T,
+    ) -> Outcome<Solution<T, A>>
+    where
+        T: BidirectionalHeuristic<A>,
+    {
+        if initial_state == goal_state {
+            self.statistics.solutions += 1;
+            self.notify_link(Hook::Solution, &initial_state, &Link::root(), || {
+                Some(initial_state.heuristic_to(&goal_state))
+            });
+            return Outcome::Solved(self.empty_solution(initial_state));
+        }
+
+        let initial_state = Rc::new(initial_state);
+        let goal_state = Rc::new(goal_state);
+        let mut forward = self.side(&initial_state, &goal_state, true);
+        let mut backward = self.side(&goal_state, &initial_state, false);
+        let mut best: Option<(A::Cost, Rc<T>)> = None;
+
+        loop {
+            self.record_frontier(forward.open.len() + backward.open.len());
+            let (expand_forward, lower_bound) =
+                match (forward.min_priority(), backward.min_priority()) {
+                    (None, None) => break,
+                    (Some(f), None) => (true, f),
+                    (None, Some(b)) => (false, b),
+                    (Some(f), Some(b)) => (f <= b, f.min_cost(b)),
+                };
+            if let Some((cost, _)) = &best {
+                if *cost <= lower_bound {
+                    break;
+                }
+            }
+            let stored = forward.links.len() + backward.links.len();
+            if let Some(outcome) = self.interruption(stored) {
+                return outcome;
+            }
+            if expand_forward {
+                self.expand_side(&mut forward, &backward, &mut best);
+            } else {
+                self.expand_side(&mut backward, &forward, &mut best);
+            }
+        }
+
+        let Some((_, meeting)) = best else {
+            return Outcome::Exhausted;
+        };
+        self.statistics.solutions += 1;
+        self.notify_link(Hook::Solution, &meeting, &forward.links[&meeting], || {
+            Some(meeting.heuristic_to(&forward.target))
+        });
+        Outcome::Solved(self.splice(&meeting, &forward.links, &backward.links))
+    }
+
+    /// Calls the observer, if any, with a state and its link
+    fn notify_link(
+        &mut self,
+        hook: Hook,
+        state: &T,
+        link: &Link<T, A>,
+        h: impl FnOnce() -> Option<A::Cost>,
+    ) {
+        if let Some(observer) = &mut self.observer {
+            let event = Event {
+                state,
+                parent: link
+                    .next
+                    .as_ref()
+                    .map(|(next, action)| (next.as_ref(), action)),
+                g: link.cost,
+                h: h(),
+                depth: link.depth,
+            };
+            observer.call(hook, &event);
+        }
+    }
+
+    fn empty_solution(&self, state: T) -> Solution<T, A> {
+        Solution {
+            actions: Vec::new(),
+            states: vec![Rc::new(state)],
+            cost: A::Cost::ZERO,
+            depth: 0,
+            nodes_explored: self.statistics.nodes_explored,
+            state: PhantomData,
+        }
+    }
+
+    /// Plan through the meeting point of the forward and backward searches
+    fn splice(
+        &self,
+        meeting: &Rc<T>,
+        forward: &Links<T, A>,
+        backward: &Links<T, A>,
+    ) -> Solution<T, A> {
+        let mut actions = Vec::new();
+        let mut states = vec![Rc::clone(meeting)];
+        let mut current_state = meeting;
+        while let Some((parent, action)) = &forward[current_state].next {
+            actions.push(action.clone());
+            states.push(Rc::clone(parent));
+            current_state = parent;
+        }
+        actions.reverse();
+        states.reverse();
+
+        current_state = meeting;
+        while let Some((child, action)) = &backward[current_state].next {
+            actions.push(action.clone());
+            states.push(Rc::clone(child));
+            current_state = child;
+        }
+        Solution {
+            depth: actions.len(),
+            actions,
+            states,
+            cost: forward[meeting].cost + backward[meeting].cost,
+            nodes_explored: self.statistics.nodes_explored,
+            state: PhantomData,
+        }
+    }
+}
+
+impl<T, A, C> Search<T, A, C>
+where
+    T: PredecessorState<A> + Hash + Eq,
+    A: Action + Clone,
+    C: ClosedList<Rc<T>, A::Cost>,
+{
+    /// Replaces a whole layer with the next one, returning the meeting point
+    /// with the other search of the shortest plan, if any, or the outcome
+    /// that stopped the search in the middle of the layer
+    fn expand_layer(
+        &mut self,
+        layer: &mut Vec<Rc<T>>,
+        links: &mut Links<T, A>,
+        other: &Links<T, A>,
+        forward: bool,
+    ) -> Result<Option<Rc<T>>, Outcome<Solution<T, A>>> {
+        let mut next_layer = Vec::new();
+        let mut meeting: Option<(usize, Rc<T>)> = None;
+
+        for current_state in mem::take(layer) {
+            if let Some(outcome) = self.interruption(links.len() + other.len()) {
+                return Err(outcome);
+            }
+            let link = &links[&current_state];
+            let (cost, depth) = (link.cost, link.depth + 1);
+            self.record_expansion(depth - 1);
+            self.notify_link(Hook::Expanded, &current_state, link, || None);
+            self.report_progress(next_layer.len(), None);
+
+            for (neighbour, action) in neighbours(current_state.as_ref(), forward) {
+                self.statistics.nodes_generated += 1;
+                let action_cost = step_cost(current_state.as_ref(), &neighbour, &action, forward);
+                let link = Link {
+                    cost: cost + action_cost,
+                    next: Some((Rc::clone(&current_state), action)),
+                    depth,
+                    closed: false,
+                };
+                self.notify_link(Hook::Generated, &neighbour, &link, || None);
+                if links.contains_key(&neighbour) {
+                    self.statistics.duplicates += 1;
+                    self.notify_link(Hook::Duplicate, &neighbour, &link, || None);
+                    continue;
+                }
+                let neighbour = Rc::new(neighbour);
+                links.insert(Rc::clone(&neighbour), link);
+                if let Some(other_link) = other.get(&neighbour) {
+                    let length = depth + other_link.depth;
+                    if meeting.as_ref().is_none_or(|(best, _)| length < *best) {
+                        meeting = Some((length, Rc::clone(&neighbour)));
+                    }
+                }
+                next_layer.push(neighbour);
+            }
+        }
+        *layer = next_layer;
+        Ok(meeting.map(|(_, state)| state))
+    }
+}
+
+impl<T, A, C> Search<T, A, C>
+where
+    T: PredecessorState<A> + BidirectionalHeuristic<A> + Hash + Eq,
+    A: Action + Clone,
+    C: ClosedList<Rc<T>, A::Cost>,
+{
+    fn side(&self, start: &Rc<T>, target: &Rc<T>, forward: bool) -> Side<T, A> {
+        let mut side = Side {
+            forward,
+            target: Rc::clone(target),
+            links: HashMap::new(),
+            open: PrioList::with_tie_break(self.tie_break),
+        };
+        side.links.insert(Rc::clone(start), Link::root());
+        let priority = start.heuristic_to(target).max_cost(A::Cost::ZERO);
+        side.open
+            .add((priority, A::Cost::ZERO, Rc::clone(start)), priority);
+        side
+    }
+
+    /// Expands the best state of `side`, updating `best` with the cheapest
+    /// meeting point found with the `other` search
+    fn expand_side(
+        &mut self,
+        side: &mut Side<T, A>,
+        other: &Side<T, A>,
+        best: &mut Option<(A::Cost, Rc<T>)>,
+    ) {
+        let Some((priority, cost, current_state)) = side.open.get() else {
+            return;
+        };
+        let target = Rc::clone(&side.target);
+        let link = side.links.get_mut(&current_state).unwrap();
+        // to ignore closed states and outdated entries
+        if link.closed || cost > link.cost {
+            self.statistics.duplicates += 1;
+            self.notify_link(Hook::Duplicate, &current_state, link, || {
+                Some(current_state.heuristic_to(&target))
+            });
+            return;
+        }
+        link.closed = true;
+        let depth = link.depth + 1;
+        self.record_expansion(depth - 1);
+        self.notify_link(Hook::Expanded, &current_state, link, || {
+            Some(current_state.heuristic_to(&target))
+        });
+        self.report_progress(side.open.len() + other.open.len(), Some(priority));
+
+        for (neighbour, action) in neighbours(current_state.as_ref(), side.forward) {
+            self.statistics.nodes_generated += 1;
+            let neighbour_cost =
+                cost + step_cost(current_state.as_ref(), &neighbour, &action, side.forward);
+            let neighbour_link = Link {
+                next: Some((Rc::clone(&current_state), action)),
+                cost: neighbour_cost,
+                depth,
+                closed: false,
+            };
+            let h = || Some(neighbour.heuristic_to(&target));
+            self.notify_link(Hook::Generated, &neighbour, &neighbour_link, h);
+            if let Some(link) = side.links.get(&neighbour) {
+                if link.cost <= neighbour_cost {
+                    self.statistics.duplicates += 1;
+                    self.notify_link(Hook::Duplicate, &neighbour, &neighbour_link, h);
+                    continue;
+                }
+                if link.closed {
+                    self.statistics.reopened += 1;
+                }
+            }
+
+            let neighbour = Rc::new(neighbour);
+            if let Some(other_link) = other.links.get(&neighbour) {
+                let plan_cost = neighbour_cost + other_link.cost;
+                if best
+                    .as_ref()
+                    .is_none_or(|(best_cost, _)| plan_cost < *best_cost)
+                {
+                    *best = Some((plan_cost, Rc::clone(&neighbour)));
+                }
+            }
+            let priority = (neighbour_cost + neighbour.heuristic_to(&target))
+                .max_cost(neighbour_cost + neighbour_cost);
+            side.links.insert(Rc::clone(&neighbour), neighbour_link);
+            side.open
+                .add((priority, neighbour_cost, neighbour), priority);
+        }
+    }
+}
+
+/// Cost of the action between a state and its neighbour, applied in the
+/// state in the forward search and in the neighbour in the backward one
+fn step_cost<T, A>(state: &T, neighbour: &T, action: &A, forward: bool) -> A::Cost
+where
+    T: PredecessorState<A>,
+    A: Action,
+{
+    match forward {
+        true => state.action_cost(action),
+        false => neighbour.action_cost(action),
+    }
+}
+
+/// Successors of the state in the forward search, and predecessors in the
+/// backward one
+fn neighbours<T, A>(state: &T, forward: bool) -> Vec<(T, A)>
+where
+    T: PredecessorState<A>,
+    A: Action,
+{
+    match forward {
+        true => state
+            .get_applicable_actions()
+            .into_iter()
+            .map(|a| (state.apply_action(&a), a))
+            .collect(),
+        false => state.get_predecessors(),
+    }
+}
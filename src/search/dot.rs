@@ -0,0 +1,241 @@
+use std::collections::{HashMap, HashSet};
+use std::fmt::{Debug, Write as _};
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use super::{Action, Event, Observer};
+use crate::cost::Cost;
+
+struct DotNode<A: Action> {
+    label: String,
+    g: A::Cost,
+    h: Option<A::Cost>,
+    /// Order of the first expansion of the state, if it was expanded
+    order: Option<usize>,
+    /// Edge used to reach the state when it was last expanded, which is the
+    /// cheapest one after a reopening
+    parent: Option<usize>,
+    cutoff: bool,
+    solution: bool,
+}
+
+struct DotEdge {
+    from: usize,
+    to: usize,
+    label: String,
+    duplicate: bool,
+    solution: bool,
+}
+
+/// Observer that records the states explored by a search and exports them
+/// as a GraphViz DOT graph. Each state is drawn with its label, `g`, `h`
+/// and `f` when there is a heuristic, and the order of its first
+/// expansion, and each edge with its action. Expanded states are boxes,
+/// states beyond the depth limit are dotted, the edges pruned as
+/// duplicates are dashed and the solution paths are red.
+///
+/// States are identified by their label, so the state formatter has to
+/// tell apart different states. In bidirectional searches, the backward
+/// edges point to the state the action leads to, and only the forward half
+/// of the solution path is highlighted.
+pub struct DotExport<T, A: Action> {
+    format_state: Box<dyn Fn(&T) -> String>,
+    format_action: Box<dyn Fn(&A) -> String>,
+    nodes: Vec<DotNode<A>>,
+    edges: Vec<DotEdge>,
+    node_ids: HashMap<String, usize>,
+    edge_ids: HashMap<(usize, usize, String), usize>,
+    expanded: usize,
+}
+
+impl<T, A: Action + Debug> DotExport<T, A> {
+    /// Export labelling states with `format_state` and actions with their
+    /// `Debug` representation
+    pub fn new(format_state: impl Fn(&T) -> String + 'static) -> Self {
+        DotExport {
+            format_state: Box::new(format_state),
+            format_action: Box::new(|action| format!("{action:?}")),
+            nodes: Vec::new(),
+            edges: Vec::new(),
+            node_ids: HashMap::new(),
+            edge_ids: HashMap::new(),
+            expanded: 0,
+        }
+    }
+}
+
+impl<T, A: Action> DotExport<T, A> {
+    pub fn set_action_format(&mut self, format_action: impl Fn(&A) -> String + 'static) {
+        self.format_action = Box::new(format_action);
+    }
+
+    /// Forgets the states recorded, to export the next run on its own
+    pub fn clear(&mut self) {
+        self.nodes.clear();
+        self.edges.clear();
+        self.node_ids.clear();
+        self.edge_ids.clear();
+        self.expanded = 0;
+    }
+
+    pub fn to_dot(&self) -> String {
+        let mut dot = String::from("digraph search {\n");
+        for (id, node) in self.nodes.iter().enumerate() {
+            let mut label = node.label.clone();
+            if let Some(order) = node.order {
+                let _ = write!(label, "\n#{order}");
+            }
+            let _ = write!(label, "\ng={}", node.g);
+            if let Some(h) = node.h {
+                let _ = write!(label, " h={h} f={}", node.g + h);
+            }
+            let _ = write!(dot, "  n{id} [label={}", quote(&label));
+            if node.order.is_some() {
+                dot.push_str(", shape=box");
+            }
+            if node.cutoff {
+                dot.push_str(", style=dotted");
+            }
+            if node.solution {
+                dot.push_str(", color=red, penwidth=2");
+            }
+            dot.push_str("];\n");
+        }
+        for edge in &self.edges {
+            let _ = write!(
+                dot,
+                "  n{} -> n{} [label={}",
+                edge.from,
+                edge.to,
+                quote(&edge.label)
+            );
+            if edge.duplicate {
+                dot.push_str(", style=dashed, color=gray");
+            }
+            if edge.solution {
+                dot.push_str(", color=red, penwidth=2");
+            }
+            dot.push_str("];\n");
+        }
+        dot.push_str("}\n");
+        dot
+    }
+
+    /// Writes `to_dot` to the file at `path`
+    pub fn write_dot(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        fs::write(path, self.to_dot())
+    }
+
+    /// Records the state of the event with its costs, and the edge from its
+    /// parent, returning their ids
+    fn record(&mut self, event: &Event<T, A>) -> (usize, Option<usize>) {
+        let node = self.node(event.state, event.g, event.h);
+        let edge = event.parent.map(|(parent, action)| {
+            let parent = self.node_id(parent);
+            let label = (self.format_action)(action);
+            let next_id = self.edges.len();
+            *self
+                .edge_ids
+                .entry((parent, node, label.clone()))
+                .or_insert_with(|| {
+                    self.edges.push(DotEdge {
+                        from: parent,
+                        to: node,
+                        label,
+                        duplicate: false,
+                        solution: false,
+                    });
+                    next_id
+                })
+        });
+        (node, edge)
+    }
+
+    /// Id of the state, keeping the lowest `g` seen for it
+    fn node(&mut self, state: &T, g: A::Cost, h: Option<A::Cost>) -> usize {
+        let id = self.node_id(state);
+        let node = &mut self.nodes[id];
+        if g < node.g {
+            node.g = g;
+        }
+        node.h = h.or(node.h);
+        id
+    }
+
+    fn node_id(&mut self, state: &T) -> usize {
+        let label = (self.format_state)(state);
+        let next_id = self.nodes.len();
+        *self.node_ids.entry(label.clone()).or_insert_with(|| {
+            self.nodes.push(DotNode {
+                label,
+                g: A::Cost::MAX,
+                h: None,
+                order: None,
+                parent: None,
+                cutoff: false,
+                solution: false,
+            });
+            next_id
+        })
+    }
+}
+
+impl<T, A: Action> Observer<T, A> for DotExport<T, A> {
+    fn expanded(&mut self, event: &Event<T, A>) {
+        let (node, edge) = self.record(event);
+        let node = &mut self.nodes[node];
+        if node.order.is_none() {
+            self.expanded += 1;
+            node.order = Some(self.expanded);
+        }
+        node.parent = edge;
+    }
+
+    fn generated(&mut self, event: &Event<T, A>) {
+        self.record(event);
+    }
+
+    fn duplicate(&mut self, event: &Event<T, A>) {
+        if let (_, Some(edge)) = self.record(event) {
+            self.edges[edge].duplicate = true;
+        }
+    }
+
+    fn depth_cutoff(&mut self, event: &Event<T, A>) {
+        let (node, _) = self.record(event);
+        self.nodes[node].cutoff = true;
+    }
+
+    fn solution(&mut self, event: &Event<T, A>) {
+        let (node, mut edge) = self.record(event);
+        self.nodes[node].solution = true;
+        let mut visited = HashSet::from([node]);
+        while let Some(id) = edge {
+            let edge_ref = &mut self.edges[id];
+            edge_ref.solution = true;
+            edge_ref.duplicate = false;
+            let parent = edge_ref.from;
+            if !visited.insert(parent) {
+                break;
+            }
+            self.nodes[parent].solution = true;
+            edge = self.nodes[parent].parent;
+        }
+    }
+}
+
+/// DOT string literal with the value, escaping quotes and line breaks
+fn quote(value: &str) -> String {
+    let mut quoted = String::from("\"");
+    for c in value.chars() {
+        match c {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            '\n' => quoted.push_str("\\n"),
+            c => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
@@ -0,0 +1,128 @@
+use std::any::Any;
+use std::error::Error;
+use std::fmt::{self, Debug, Display, Formatter};
+
+use super::{Limit, Outcome, Solution};
+
+/// Result of the searches: `Ok(None)` when the whole state space was
+/// explored without finding any solution
+pub type SearchResult<S, P = ()> = Result<S, SearchError<P>>;
+
+/// Result of the searches of several solutions, which keep the ones found
+/// before being stopped in the `partial` of the error
+pub type SolutionsResult<T, A> = SearchResult<Vec<Solution<T, A>>, Vec<Solution<T, A>>>;
+
+/// Reason a search stopped before telling whether there is a solution.
+/// Every variant names the algorithm that failed, as in `Report`. `P` is
+/// what a stopped search found until then: the solutions of the searches
+/// of several ones, nothing for the others.
+#[derive(Debug, Clone, PartialEq)]
+pub enum SearchError<P = ()> {
+    /// A parameter of the search is out of its range
+    InvalidParameter {
+        algorithm: &'static str,
+        message: String,
+    },
+    /// The search stopped at one of its `Limits`
+    LimitReached {
+        algorithm: &'static str,
+        limit: Limit,
+        partial: P,
+    },
+    /// The cancellation flag was raised, see `Search::set_cancel_flag`
+    Cancelled { algorithm: &'static str, partial: P },
+    /// The domain panicked while a state was expanded, e.g. applying an
+    /// action that is not applicable
+    Panicked {
+        algorithm: &'static str,
+        message: String,
+    },
+}
+
+impl<P> SearchError<P> {
+    pub fn algorithm(&self) -> &'static str {
+        match self {
+            SearchError::InvalidParameter { algorithm, .. }
+            | SearchError::LimitReached { algorithm, .. }
+            | SearchError::Cancelled { algorithm, .. }
+            | SearchError::Panicked { algorithm, .. } => algorithm,
+        }
+    }
+
+    /// What the search found before it was stopped, if it was
+    pub fn into_partial(self) -> Option<P> {
+        match self {
+            SearchError::LimitReached { partial, .. } | SearchError::Cancelled { partial, .. } => {
+                Some(partial)
+            }
+            SearchError::InvalidParameter { .. } | SearchError::Panicked { .. } => None,
+        }
+    }
+
+    pub(super) fn panicked(algorithm: &'static str, payload: Box<dyn Any + Send>) -> Self {
+        let message = match payload.downcast::<String>() {
+            Ok(message) => *message,
+            Err(payload) => match payload.downcast::<&str>() {
+                Ok(message) => message.to_string(),
+                Err(_) => "unknown panic".to_string(),
+            },
+        };
+        SearchError::Panicked { algorithm, message }
+    }
+}
+
+impl<P> Display for SearchError<P> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            SearchError::InvalidParameter { algorithm, message } => {
+                write!(f, "{algorithm}: invalid parameter, {message}")
+            }
+            SearchError::LimitReached {
+                algorithm, limit, ..
+            } => {
+                let limit = match limit {
+                    Limit::Nodes => "node",
+                    Limit::Time => "time",
+                    Limit::Memory => "memory",
+                };
+                write!(f, "{algorithm}: {limit} limit reached")
+            }
+            SearchError::Cancelled { algorithm, .. } => write!(f, "{algorithm}: cancelled"),
+            SearchError::Panicked { algorithm, message } => {
+                write!(f, "{algorithm}: panicked expanding a state, {message}")
+            }
+        }
+    }
+}
+
+impl<P: Debug> Error for SearchError<P> {}
+
+impl<S, P> Outcome<S, P> {
+    pub(super) fn into_result(self, algorithm: &'static str) -> SearchResult<Option<S>, P> {
+        match self {
+            Outcome::Solved(solution) => Ok(Some(solution)),
+            Outcome::Exhausted => Ok(None),
+            Outcome::LimitReached(limit, partial) => Err(SearchError::LimitReached {
+                algorithm,
+                limit,
+                partial,
+            }),
+            Outcome::Cancelled(partial) => Err(SearchError::Cancelled { algorithm, partial }),
+        }
+    }
+}
+
+/// Fails with `SearchError::InvalidParameter` unless `valid`
+pub(super) fn check_parameter(
+    algorithm: &'static str,
+    valid: bool,
+    message: &str,
+) -> SearchResult<()> {
+    match valid {
+        true => Ok(()),
+        false => Err(SearchError::InvalidParameter {
+            algorithm,
+            message: message.to_string(),
+        }),
+    }
+}
@@ -0,0 +1,175 @@
+use std::fmt::{self, Debug, Formatter};
+use std::ops::Deref;
+use std::rc::Rc;
+use std::sync::Arc;
+
+use super::{Action, State};
+use crate::cost::Cost;
+
+/// Reference counted pointer the nodes share their states and parents
+/// with: `Rc` in `Search`, `Arc` in `ParallelSearch`
+pub trait SharedPointer<T>: Clone + Deref<Target = T> {
+    /// Same pointer to a `U`
+    type Of<U>: Clone + Deref<Target = U>;
+
+    fn new(value: T) -> Self;
+
+    fn try_unwrap_of<U>(pointer: Self::Of<U>) -> Option<U>;
+}
+
+impl<T> SharedPointer<T> for Rc<T> {
+    type Of<U> = Rc<U>;
+
+    fn new(value: T) -> Self {
+        Rc::new(value)
+    }
+
+    fn try_unwrap_of<U>(pointer: Rc<U>) -> Option<U> {
+        Rc::try_unwrap(pointer).ok()
+    }
+}
+
+impl<T> SharedPointer<T> for Arc<T> {
+    type Of<U> = Arc<U>;
+
+    fn new(value: T) -> Self {
+        Arc::new(value)
+    }
+
+    fn try_unwrap_of<U>(pointer: Arc<U>) -> Option<U> {
+        Arc::try_unwrap(pointer).ok()
+    }
+}
+
+/// Node of the search tree: a state with a link to the node it was
+/// generated from, the action applied to it, the cost `g` of the path from
+/// the initial state and its depth. Plans are rebuilt walking back the
+/// parent links, so states do not need to store their own.
+pub struct Node<T, A: Action, P: SharedPointer<T> = Rc<T>> {
+    state: P,
+    parent: Option<P::Of<Node<T, A, P>>>,
+    action: Option<A>,
+    cost: A::Cost,
+    depth: usize,
+}
+
+impl<T, A, P> Node<T, A, P>
+where
+    T: State<A>,
+    A: Action,
+    P: SharedPointer<T>,
+{
+    pub(crate) fn root(state: T) -> Self {
+        Node {
+            cost: state.get_solution_cost().unwrap_or(A::Cost::ZERO),
+            state: P::new(state),
+            parent: None,
+            action: None,
+            depth: 0,
+        }
+    }
+
+    /// Node generated applying `action` to the state of `node`
+    pub(crate) fn child(node: &P::Of<Self>, action: A) -> Self {
+        let state = node.state.apply_action(&action);
+        let cost = node.cost + node.state.action_cost(&action);
+        debug_check_cost(&state, cost);
+        Node {
+            cost,
+            state: P::new(state),
+            parent: Some(node.clone()),
+            action: Some(action),
+            depth: node.depth + 1,
+        }
+    }
+}
+
+impl<T, A> Node<T, A>
+where
+    T: State<A>,
+    A: Action,
+{
+    /// Nodes generated applying each applicable action to the state
+    pub(crate) fn children(self: &Rc<Self>) -> impl Iterator<Item = Rc<Self>> + '_ {
+        self.state
+            .get_applicable_actions()
+            .into_iter()
+            .map(move |action| Rc::new(Node::child(self, action)))
+    }
+}
+
+impl<T, A: Action, P: SharedPointer<T>> Node<T, A, P> {
+    pub fn state(&self) -> &P {
+        &self.state
+    }
+
+    pub fn parent(&self) -> Option<&P::Of<Node<T, A, P>>> {
+        self.parent.as_ref()
+    }
+
+    /// Action that generated this node from its parent, `None` in the root
+    pub fn action(&self) -> Option<&A> {
+        self.action.as_ref()
+    }
+
+    /// Cost of the path from the initial state
+    pub fn cost(&self) -> A::Cost {
+        self.cost
+    }
+
+    pub fn depth(&self) -> usize {
+        self.depth
+    }
+
+    /// Plan from the initial state to this node
+    pub fn actions(&self) -> Vec<A>
+    where
+        A: Clone,
+    {
+        let mut actions = Vec::with_capacity(self.depth);
+        let mut node = self;
+        while let (Some(action), Some(parent)) = (&node.action, &node.parent) {
+            actions.push(action.clone());
+            node = parent;
+        }
+        actions.reverse();
+        actions
+    }
+}
+
+impl<T: Debug, A: Action + Debug, P: SharedPointer<T>> Debug for Node<T, A, P> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Node")
+            .field("state", &*self.state)
+            .field("parent", &self.parent.as_deref())
+            .field("action", &self.action)
+            .field("cost", &self.cost)
+            .field("depth", &self.depth)
+            .finish()
+    }
+}
+
+impl<T, A: Action, P: SharedPointer<T>> Drop for Node<T, A, P> {
+    // unlinks the chain of parents owned only by this node iteratively, as
+    // the default recursive drop can overflow the stack in deep searches
+    fn drop(&mut self) {
+        let mut parent = self.parent.take();
+        while let Some(node) = parent {
+            parent = P::try_unwrap_of(node).and_then(|mut node| node.parent.take());
+        }
+    }
+}
+
+/// Flags states whose own cost disagrees with the cost of the path
+/// accumulated by the search
+fn debug_check_cost<T: State<A>, A: Action>(state: &T, cost: A::Cost) {
+    if cfg!(debug_assertions) {
+        if let Some(state_cost) = state.get_solution_cost() {
+            let (expected, actual) = (cost.to_f64(), state_cost.to_f64());
+            debug_assert!(
+                (actual - expected).abs() <= 1e-3 * expected.abs().max(1.0),
+                "state reports a solution cost of {state_cost}, but the path to it costs {cost}"
+            );
+        }
+    }
+}
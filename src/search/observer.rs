@@ -0,0 +1,109 @@
+use std::cell::RefCell;
+use std::fmt::{Debug, Formatter, Result};
+use std::rc::Rc;
+
+use super::{Action, Node};
+
+/// State involved in a search event, with the costs known by the search
+#[derive(Debug)]
+pub struct Event<'a, T, A: Action> {
+    pub state: &'a T,
+    /// State this one was generated from and the action applied to it, `None`
+    /// for the initial state. In the backward half of a bidirectional
+    /// search, the state reached applying the action to this one.
+    pub parent: Option<(&'a T, &'a A)>,
+    pub g: A::Cost,
+    /// Heuristic value, for the searches guided by one
+    pub h: Option<A::Cost>,
+    pub depth: usize,
+}
+
+impl<'a, T, A: Action> Event<'a, T, A> {
+    pub(super) fn from_node(node: &'a Node<T, A>, h: Option<A::Cost>) -> Self {
+        Event {
+            state: node.state(),
+            parent: node
+                .parent()
+                .zip(node.action())
+                .map(|(parent, action)| (parent.state().as_ref(), action)),
+            g: node.cost(),
+            h,
+            depth: node.depth(),
+        }
+    }
+}
+
+/// Hooks called by the searches as they run, see `Search::set_observer`.
+/// All of them do nothing by default.
+pub trait Observer<T, A: Action> {
+    /// State taken from the open list to generate its children
+    fn expanded(&mut self, _event: &Event<T, A>) {}
+    /// State generated applying an action, before checking if it is a
+    /// duplicate
+    fn generated(&mut self, _event: &Event<T, A>) {}
+    /// State discarded because it was already reached with a lower or equal
+    /// cost
+    fn duplicate(&mut self, _event: &Event<T, A>) {}
+    /// State not expanded because it is beyond the depth limit, or beyond
+    /// the `g + h` threshold in IDA*
+    fn depth_cutoff(&mut self, _event: &Event<T, A>) {}
+    fn solution(&mut self, _event: &Event<T, A>) {}
+}
+
+/// Shared observer, so the caller can keep a handle to it while it is
+/// attached to a search
+impl<T, A: Action, O: Observer<T, A>> Observer<T, A> for Rc<RefCell<O>> {
+    fn expanded(&mut self, event: &Event<T, A>) {
+        self.borrow_mut().expanded(event)
+    }
+
+    fn generated(&mut self, event: &Event<T, A>) {
+        self.borrow_mut().generated(event)
+    }
+
+    fn duplicate(&mut self, event: &Event<T, A>) {
+        self.borrow_mut().duplicate(event)
+    }
+
+    fn depth_cutoff(&mut self, event: &Event<T, A>) {
+        self.borrow_mut().depth_cutoff(event)
+    }
+
+    fn solution(&mut self, event: &Event<T, A>) {
+        self.borrow_mut().solution(event)
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub(super) enum Hook {
+    Expanded,
+    Generated,
+    Duplicate,
+    DepthCutoff,
+    Solution,
+}
+
+pub(super) struct BoxedObserver<T, A: Action>(Box<dyn Observer<T, A>>);
+
+impl<T, A: Action> BoxedObserver<T, A> {
+    pub(super) fn new(observer: impl Observer<T, A> + 'static) -> Self {
+        BoxedObserver(Box::new(observer))
+    }
+
+    pub(super) fn call(&mut self, hook: Hook, event: &Event<T, A>) {
+        let observer = &mut self.0;
+        match hook {
+            Hook::Expanded => observer.expanded(event),
+            Hook::Generated => observer.generated(event),
+            Hook::Duplicate => observer.duplicate(event),
+            Hook::DepthCutoff => observer.depth_cutoff(event),
+            Hook::Solution => observer.solution(event),
+        }
+    }
+}
+
+impl<T, A: Action> Debug for BoxedObserver<T, A> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        write!(f, "Observer")
+    }
+}
@@ -0,0 +1,370 @@
+use std::cmp;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use super::error::check_parameter;
+use super::{
+    Action, Limit, Limits, Node, Outcome, Report, SearchError, SearchResult, Solution, State,
+    StateHeuristic, Statistics,
+};
+use crate::openlist::prio_list::PrioList;
+use crate::openlist::PriorityOpenList;
+
+const ALGORITHM: &str = "hda_star";
+
+/// Node of the parallel search, shared between threads
+type SharedNode<T, A> = Node<T, A, Arc<T>>;
+
+/// State shared by the workers of a run
+struct Shared<'a, T, A: Action> {
+    /// Nodes sent and not processed yet, in the channels or the open lists.
+    /// A node counts its children before it is discounted, so the count
+    /// only reaches 0 when there is nothing left to do anywhere.
+    pending: AtomicUsize,
+    /// Raised when a worker panics or stops the run, to stop the others
+    abort: AtomicBool,
+    /// Cancellation or limit that stopped the run, set by the first worker
+    /// to find it
+    stop: OnceLock<Outcome<SharedSolution<T, A>>>,
+    cancel: Option<&'a AtomicBool>,
+    limits: Limits,
+    start: Instant,
+    /// States expanded by all the workers, for the node limit
+    explored: AtomicUsize,
+    /// States in the closed lists of all the workers, for the memory limit
+    stored: AtomicUsize,
+    /// States in the open lists of all the workers, and its peak
+    open: AtomicUsize,
+    max_open: AtomicUsize,
+    best: Mutex<Option<Arc<SharedNode<T, A>>>>,
+    /// Times `best` was improved, for the workers to know when their copy
+    /// of its cost is outdated without locking it
+    improvements: AtomicUsize,
+}
+
+impl<T, A: Action> Shared<'_, T, A> {
+    fn best_cost(&self) -> Option<A::Cost> {
+        self.best.lock().unwrap().as_ref().map(|node| node.cost())
+    }
+
+    /// Outcome stopping the run before the next expansion, if the search
+    /// was cancelled or reached one of its limits, as in `Search`
+    fn interruption<S>(&self) -> Option<Outcome<S>> {
+        if self
+            .cancel
+            .is_some_and(|cancel| cancel.load(Ordering::Relaxed))
+        {
+            return Some(Outcome::Cancelled(()));
+        }
+        let limits = &self.limits;
+        let limit = if limits
+            .max_nodes
+            .is_some_and(|max| self.explored.load(Ordering::Relaxed) >= max)
+        {
+            Limit::Nodes
+        } else if limits.max_stored.is_some_and(|max| {
+            self.open.load(Ordering::Relaxed) + self.stored.load(Ordering::Relaxed) > max
+        }) {
+            Limit::Memory
+        } else if limits
+            .max_time
+            .is_some_and(|max| self.start.elapsed() >= max)
+        {
+            Limit::Time
+        } else {
+            return None;
+        };
+        Some(Outcome::LimitReached(limit, ()))
+    }
+}
+
+/// Raises the abort flag if the worker holding it panics
+struct AbortOnPanic<'a>(&'a AtomicBool);
+
+impl Drop for AbortOnPanic<'_> {
+    fn drop(&mut self) {
+        if thread::panicking() {
+            self.0.store(true, Ordering::SeqCst);
+        }
+    }
+}
+
+/// Solution of a `ParallelSearch`, with the states shared by the workers
+pub type SharedSolution<T, A> = Solution<T, A, Arc<T>>;
+
+/// Hash distributed A* (HDA*): each state is owned by the worker thread its
+/// hash points to, which keeps it in its own open and closed lists. The
+/// states generated are sent to their owners over channels, and the search
+/// goes on until no state can improve the best solution found, so its cost
+/// is the same as the one of `Search::search_a_start_first`.
+///
+/// States are shared between the threads with `Arc`, so they have to be
+/// `Send` and `Sync`, and so are the ones of the solution.
+#[derive(Debug, Clone)]
+pub struct ParallelSearch {
+    /// Statistics of the last run, added up over the workers. `max_open`
+    /// is the peak of the open lists of all the workers together.
+    pub statistics: Statistics,
+    threads: usize,
+    limits: Limits,
+    cancel: Option<Arc<AtomicBool>>,
+}
+
+impl ParallelSearch {
+    /// Search with `threads` workers, which must be greater than 0
+    pub fn new(threads: usize) -> Self {
+        ParallelSearch {
+            statistics: Statistics::default(),
+            threads,
+            limits: Limits::default(),
+            cancel: None,
+        }
+    }
+
+    /// Search with a worker per available core
+    pub fn with_available_parallelism() -> Self {
+        ParallelSearch::new(thread::available_parallelism().map_or(1, |n| n.get()))
+    }
+
+    pub fn threads(&self) -> usize {
+        self.threads
+    }
+
+    /// Flag polled by the workers before each expansion: once raised, the
+    /// search stops with `SearchError::Cancelled`
+    pub fn set_cancel_flag(&mut self, cancel: Arc<AtomicBool>) {
+        self.cancel = Some(cancel);
+    }
+
+    /// Budgets for the search, counted over all the workers
+    pub fn set_limits(&mut self, limits: Limits) {
+        self.limits = limits;
+    }
+
+    /// Statistics of the last run
+    pub fn report(&self) -> Report<'_> {
+        Report {
+            algorithm: ALGORITHM,
+            limits: self.limits,
+            statistics: &self.statistics,
+        }
+    }
+
+    pub fn search_hda_star_first<T, A>(
+        &mut self,
+        initial_state: T,
+    ) -> SearchResult<Option<SharedSolution<T, A>>>
+    where
+        T: State<A> + StateHeuristic<A> + Hash + Eq + Send + Sync,
+        A: Action + Clone + Send + Sync,
+        A::Cost: Send + Sync,
+    {
+        self.statistics = Statistics::default();
+        check_parameter(ALGORITHM, self.threads > 0, "the threads must be > 0")?;
+        let start = Instant::now();
+        let shared = Shared {
+            pending: AtomicUsize::new(1),
+            abort: AtomicBool::new(false),
+            stop: OnceLock::new(),
+            cancel: self.cancel.as_deref(),
+            limits: self.limits,
+            start,
+            explored: AtomicUsize::new(0),
+            stored: AtomicUsize::new(0),
+            open: AtomicUsize::new(0),
+            max_open: AtomicUsize::new(0),
+            best: Mutex::new(None),
+            improvements: AtomicUsize::new(0),
+        };
+        let (senders, receivers): (Vec<_>, Vec<_>) =
+            (0..self.threads).map(|_| mpsc::channel()).unzip();
+        let root = SharedNode::root(initial_state);
+        let owner = owner(root.state(), self.threads);
+        senders[owner].send(Arc::new(root)).unwrap();
+
+        let results: Vec<_> = thread::scope(|scope| {
+            let workers: Vec<_> = receivers
+                .into_iter()
+                .map(|inbox| {
+                    let worker = Worker {
+                        shared: &shared,
+                        senders: &senders,
+                        open: PrioList::new(),
+                        closed: HashMap::new(),
+                        best: None,
+                        improvements: 0,
+                        statistics: Statistics::default(),
+                    };
+                    scope.spawn(move || worker.run(inbox))
+                })
+                .collect();
+            workers.into_iter().map(|worker| worker.join()).collect()
+        });
+        for result in results {
+            match result {
+                Ok(statistics) => merge(&mut self.statistics, &statistics),
+                Err(payload) => return Err(SearchError::panicked(ALGORITHM, payload)),
+            }
+        }
+        self.statistics.max_open = shared.max_open.into_inner();
+        self.statistics.elapsed = start.elapsed();
+
+        if let Some(stop) = shared.stop.into_inner() {
+            return stop.into_result(ALGORITHM);
+        }
+        let best = shared.best.into_inner().unwrap();
+        Ok(best.map(|node| {
+            self.statistics.solutions = 1;
+            Solution::from_node(&node, self.statistics.nodes_explored)
+        }))
+    }
+}
+
+type Channel<T, A> = Sender<Arc<SharedNode<T, A>>>;
+
+/// Open list of a worker, with `(f, node)` entries
+type Frontier<T, A> = PrioList<(<A as Action>::Cost, Arc<SharedNode<T, A>>), <A as Action>::Cost>;
+
+/// Worker thread, owning the states hashed to it
+struct Worker<'a, T, A: Action> {
+    shared: &'a Shared<'a, T, A>,
+    senders: &'a [Channel<T, A>],
+    open: Frontier<T, A>,
+    /// Lowest cost known to each state owned
+    closed: HashMap<Arc<T>, A::Cost>,
+    /// Cost of the best solution when `Shared::improvements` was
+    /// `improvements`
+    best: Option<A::Cost>,
+    improvements: usize,
+    statistics: Statistics,
+}
+
+impl<T, A> Worker<'_, T, A>
+where
+    T: State<A> + StateHeuristic<A> + Hash + Eq,
+    A: Action + Clone,
+{
+    /// Processes the states received until there is nothing pending,
+    /// returning the statistics of the worker
+    fn run(mut self, inbox: Receiver<Arc<SharedNode<T, A>>>) -> Statistics {
+        let _guard = AbortOnPanic(&self.shared.abort);
+        while !self.shared.abort.load(Ordering::SeqCst) {
+            for node in inbox.try_iter() {
+                self.receive(node);
+            }
+            let Some((f, node)) = self.open.get() else {
+                if self.shared.pending.load(Ordering::SeqCst) == 0 {
+                    break;
+                }
+                if let Ok(node) = inbox.recv_timeout(Duration::from_millis(1)) {
+                    self.receive(node);
+                }
+                continue;
+            };
+            self.shared.open.fetch_sub(1, Ordering::Relaxed);
+            // to ignore outdated entries
+            if self.closed[node.state()] < node.cost() {
+                self.statistics.duplicates += 1;
+            } else if let Some(outcome) = self.shared.interruption() {
+                let _ = self.shared.stop.set(outcome);
+                self.shared.abort.store(true, Ordering::SeqCst);
+                break;
+            } else {
+                self.expand(&node, f);
+            }
+            self.shared.pending.fetch_sub(1, Ordering::SeqCst);
+        }
+        self.statistics
+    }
+
+    /// Keeps the node if it is the cheapest path known to its state
+    fn receive(&mut self, node: Arc<SharedNode<T, A>>) {
+        if let Some(&known) = self.closed.get(node.state()) {
+            if known <= node.cost() {
+                self.statistics.duplicates += 1;
+                self.shared.pending.fetch_sub(1, Ordering::SeqCst);
+                return;
+            }
+            self.statistics.reopened += 1;
+        } else {
+            self.shared.stored.fetch_add(1, Ordering::Relaxed);
+        }
+        self.closed.insert(Arc::clone(node.state()), node.cost());
+        let f = node.cost() + node.state().heuristic();
+        self.open.add((f, node), f);
+        let open = self.shared.open.fetch_add(1, Ordering::Relaxed) + 1;
+        self.shared.max_open.fetch_max(open, Ordering::Relaxed);
+    }
+
+    /// Cost of the best solution found by any worker, only locking it
+    /// after an improvement
+    fn best_cost(&mut self) -> Option<A::Cost> {
+        let improvements = self.shared.improvements.load(Ordering::Acquire);
+        if improvements != self.improvements {
+            self.improvements = improvements;
+            self.best = self.shared.best_cost();
+        }
+        self.best
+    }
+
+    /// Expands the node, unless it cannot improve the best solution,
+    /// sending its children to their owners
+    fn expand(&mut self, node: &Arc<SharedNode<T, A>>, f: A::Cost) {
+        if self.best_cost().is_some_and(|best| f >= best) {
+            return;
+        }
+        self.shared.explored.fetch_add(1, Ordering::Relaxed);
+        self.statistics.record_expansion(node.depth());
+
+        if node.state().is_solution() {
+            let mut best = self.shared.best.lock().unwrap();
+            if best.as_ref().is_none_or(|best| node.cost() < best.cost()) {
+                *best = Some(Arc::clone(node));
+                self.shared.improvements.fetch_add(1, Ordering::Release);
+            }
+            return;
+        }
+        for action in node.state().get_applicable_actions() {
+            let child = SharedNode::child(node, action);
+            self.statistics.nodes_generated += 1;
+            self.shared.pending.fetch_add(1, Ordering::SeqCst);
+            let owner = owner(child.state(), self.senders.len());
+            // fails only if the owner stopped on abort
+            let _ = self.senders[owner].send(Arc::new(child));
+        }
+    }
+}
+
+/// Worker owning the state
+fn owner<T: Hash>(state: &T, workers: usize) -> usize {
+    let mut hasher = DefaultHasher::new();
+    state.hash(&mut hasher);
+    (hasher.finish() % workers as u64) as usize
+}
+
+/// Adds the statistics of a worker to the ones of the run
+fn merge(total: &mut Statistics, worker: &Statistics) {
+    total.nodes_explored += worker.nodes_explored;
+    total.nodes_generated += worker.nodes_generated;
+    total.max_depth = cmp::max(total.max_depth, worker.max_depth);
+    total.duplicates += worker.duplicates;
+    total.reopened += worker.reopened;
+    if total.expanded_by_depth.len() < worker.expanded_by_depth.len() {
+        total
+            .expanded_by_depth
+            .resize(worker.expanded_by_depth.len(), 0);
+    }
+    for (total, worker) in total
+        .expanded_by_depth
+        .iter_mut()
+        .zip(&worker.expanded_by_depth)
+    {
+        *total += worker;
+    }
+}
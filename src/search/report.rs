@@ -0,0 +1,135 @@
+use std::fmt::Write as _;
+use std::fs::OpenOptions;
+use std::io::{self, Write as _};
+use std::path::Path;
+
+use super::{Limits, Statistics};
+
+const CSV_HEADER: &str = "algorithm,max_nodes,max_time,max_stored,nodes_explored,\
+nodes_generated,max_depth,solutions,duplicates,reopened,max_open,branching_factor,\
+elapsed,expanded_by_depth,pruned_by_layer";
+
+/// Statistics of a run with the algorithm and limits it used, exportable
+/// as JSON or CSV. Times are written in seconds, and the per-depth counts
+/// as arrays in JSON and `;` separated lists in CSV.
+#[derive(Debug, Clone, Copy)]
+pub struct Report<'a> {
+    pub algorithm: &'a str,
+    pub limits: Limits,
+    pub statistics: &'a Statistics,
+}
+
+impl Report<'_> {
+    pub fn to_json(&self) -> String {
+        let limits = &self.limits;
+        let statistics = self.statistics;
+        let mut json = String::new();
+        json.push_str("{\"algorithm\":");
+        push_json_string(&mut json, self.algorithm);
+        let _ = write!(
+            json,
+            ",\"limits\":{{\"max_nodes\":{},\"max_time\":{},\"max_stored\":{}}}",
+            optional(limits.max_nodes),
+            optional(limits.max_time.map(|time| time.as_secs_f64())),
+            optional(limits.max_stored),
+        );
+        let _ = write!(
+            json,
+            ",\"statistics\":{{\"nodes_explored\":{},\"nodes_generated\":{},\"max_depth\":{},\
+             \"solutions\":{},\"duplicates\":{},\"reopened\":{},\"max_open\":{},\
+             \"branching_factor\":{},\"elapsed\":{},\"expanded_by_depth\":[{}],\
+             \"pruned_by_layer\":[{}]}}}}",
+            statistics.nodes_explored,
+            statistics.nodes_generated,
+            statistics.max_depth,
+            statistics.solutions,
+            statistics.duplicates,
+            statistics.reopened,
+            statistics.max_open,
+            statistics.effective_branching_factor(),
+            statistics.elapsed.as_secs_f64(),
+            join(&statistics.expanded_by_depth, ","),
+            join(&statistics.pruned_by_layer, ","),
+        );
+        json
+    }
+
+    /// Names of the columns of `to_csv_row`
+    pub fn csv_header() -> &'static str {
+        CSV_HEADER
+    }
+
+    pub fn to_csv_row(&self) -> String {
+        let limits = &self.limits;
+        let statistics = self.statistics;
+        format!(
+            "{},{},{},{},{},{},{},{},{},{},{},{},{},{},{}",
+            csv_field(self.algorithm),
+            limits.max_nodes.map_or(String::new(), |n| n.to_string()),
+            limits
+                .max_time
+                .map_or(String::new(), |time| time.as_secs_f64().to_string()),
+            limits.max_stored.map_or(String::new(), |n| n.to_string()),
+            statistics.nodes_explored,
+            statistics.nodes_generated,
+            statistics.max_depth,
+            statistics.solutions,
+            statistics.duplicates,
+            statistics.reopened,
+            statistics.max_open,
+            statistics.effective_branching_factor(),
+            statistics.elapsed.as_secs_f64(),
+            join(&statistics.expanded_by_depth, ";"),
+            join(&statistics.pruned_by_layer, ";"),
+        )
+    }
+
+    /// Appends the report as a row of the CSV file at `path`, creating it
+    /// with its header if it does not exist or is empty
+    pub fn append_csv(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+        if file.metadata()?.len() == 0 {
+            writeln!(file, "{CSV_HEADER}")?;
+        }
+        writeln!(file, "{}", self.to_csv_row())
+    }
+}
+
+fn optional<T: ToString>(value: Option<T>) -> String {
+    value.map_or_else(|| "null".to_string(), |value| value.to_string())
+}
+
+fn join(values: &[usize], separator: &str) -> String {
+    values
+        .iter()
+        .map(|value| value.to_string())
+        .collect::<Vec<_>>()
+        .join(separator)
+}
+
+fn push_json_string(json: &mut String, value: &str) {
+    json.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => json.push_str("\\\""),
+            '\\' => json.push_str("\\\\"),
+            '\n' => json.push_str("\\n"),
+            '\r' => json.push_str("\\r"),
+            '\t' => json.push_str("\\t"),
+            c if c.is_control() => {
+                let _ = write!(json, "\\u{:04x}", c as u32);
+            }
+            c => json.push(c),
+        }
+    }
+    json.push('"');
+}
+
+/// Quotes the field if it contains a separator, a quote or a line break
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
@@ -0,0 +1,71 @@
+use std::marker::PhantomData;
+use std::ops::Deref;
+use std::rc::Rc;
+
+use super::{Action, Node, SharedPointer};
+
+/// Plan found by a search, with the states it goes through. The states are
+/// shared with the search through `P`: `Rc` for `Search`, and `Arc` for
+/// `ParallelSearch`, so its solutions can be sent to other threads.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Solution<T, A: Action, P = Rc<T>> {
+    /// Actions from the initial state to the solution state
+    pub actions: Vec<A>,
+    /// States reached by the plan, from the initial state to the solution
+    /// state, one more than `actions`
+    pub states: Vec<P>,
+    pub cost: A::Cost,
+    pub depth: usize,
+    /// States expanded in the run when the solution was found
+    pub nodes_explored: usize,
+    pub(super) state: PhantomData<T>,
+}
+
+impl<T, A: Action, P: SharedPointer<T>> Solution<T, A, P> {
+    /// Solution ending in `node`, rebuilt from its parent links
+    pub(crate) fn from_node(node: &Node<T, A, P>, nodes_explored: usize) -> Self
+    where
+        A: Clone,
+    {
+        let mut actions = Vec::with_capacity(node.depth());
+        let mut states = Vec::with_capacity(node.depth() + 1);
+        let mut current = node;
+        loop {
+            states.push(current.state().clone());
+            match (current.action(), current.parent()) {
+                (Some(action), Some(parent)) => {
+                    actions.push(action.clone());
+                    current = parent;
+                }
+                _ => break,
+            }
+        }
+        actions.reverse();
+        states.reverse();
+        Solution {
+            actions,
+            states,
+            cost: node.cost(),
+            depth: node.depth(),
+            nodes_explored,
+            state: PhantomData,
+        }
+    }
+}
+
+impl<T, A: Action, P: Deref<Target = T>> Solution<T, A, P> {
+    /// State reached at the end of the plan
+    pub fn solution_state(&self) -> &T {
+        self.states.last().unwrap()
+    }
+
+    pub fn into_actions(self) -> Vec<A> {
+        self.actions
+    }
+}
+
+impl<T, A: Action, P> From<Solution<T, A, P>> for Vec<A> {
+    fn from(solution: Solution<T, A, P>) -> Self {
+        solution.actions
+    }
+}
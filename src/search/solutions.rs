@@ -0,0 +1,78 @@
+use std::panic::{self, AssertUnwindSafe};
+use std::rc::Rc;
+
+use super::{Action, Node, Search, SearchError, SearchResult, Solution, State};
+use crate::closedlist::ClosedList;
+use crate::openlist::queue::Queue;
+use crate::openlist::stack::Stack;
+use crate::openlist::OpenList;
+
+/// Lazy iterator over the solutions of a breadth or depth first search,
+/// yielding each one as soon as it is found. The open and closed lists are
+/// kept between calls to `next`, so the search resumes where it stopped.
+/// The iteration ends once the state space is exhausted, or after yielding
+/// the error that stopped the search.
+pub struct Solutions<'a, T, A, C, O>
+where
+    T: State<A>,
+    A: Action,
+{
+    search: &'a mut Search<T, A, C>,
+    open_list: O,
+    stopped: bool,
+}
+
+/// Solutions in breadth first order, see `Search::iter_breadth`
+pub type BreadthSolutions<'a, T, A, C> = Solutions<'a, T, A, C, Queue<Rc<Node<T, A>>>>;
+
+/// Solutions in depth first order, see `Search::iter_depth`
+pub type DepthSolutions<'a, T, A, C> = Solutions<'a, T, A, C, Stack<Rc<Node<T, A>>>>;
+
+impl<'a, T, A, C, O> Solutions<'a, T, A, C, O>
+where
+    T: State<A>,
+    A: Action + Clone,
+    C: ClosedList<Rc<T>, A::Cost>,
+    O: OpenList<Rc<Node<T, A>>>,
+{
+    pub(super) fn new(search: &'a mut Search<T, A, C>, initial_state: T) -> Self {
+        let mut open_list = O::new();
+        open_list.add(Rc::new(Node::root(initial_state)));
+        Solutions {
+            search,
+            open_list,
+            stopped: false,
+        }
+    }
+
+    /// Search the solutions are looked for with, to check its statistics
+    pub fn search(&self) -> &Search<T, A, C> {
+        self.search
+    }
+}
+
+impl<T, A, C, O> Iterator for Solutions<'_, T, A, C, O>
+where
+    T: State<A>,
+    A: Action + Clone,
+    C: ClosedList<Rc<T>, A::Cost>,
+    O: OpenList<Rc<Node<T, A>>>,
+{
+    type Item = SearchResult<Solution<T, A>>;
+
+    fn next(&mut self) -> Option<SearchResult<Solution<T, A>>> {
+        if self.stopped {
+            return None;
+        }
+        let algorithm = self.search.algorithm;
+        let (search, open_list) = (&mut *self.search, &mut self.open_list);
+        let result =
+            match panic::catch_unwind(AssertUnwindSafe(|| search.next_solution(open_list, 0))) {
+                Ok(outcome) => outcome.into_result(algorithm),
+                Err(payload) => Err(SearchError::panicked(algorithm, payload)),
+            };
+        search.statistics.elapsed = search.run_start.elapsed();
+        self.stopped = !matches!(result, Ok(Some(_)));
+        result.transpose()
+    }
+}
@@ -0,0 +1,139 @@
+use rustsearch::search::{Action, BidirectionalHeuristic, PredecessorState, State, StateHeuristic};
+use std::hash::{Hash, Hasher};
+use std::sync::Arc;
+
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct Edge {
+    pub from: usize,
+    pub to: usize,
+    pub cost: f32,
+}
+
+impl Action for Edge {
+    type Cost = f32;
+
+    fn cost(&self) -> f32 {
+        self.cost
+    }
+}
+
+/// Weighted directed graph, with an optional heuristic value per node
+#[derive(Debug)]
+pub struct Graph {
+    edges: Vec<Vec<Edge>>,
+    reverse_edges: Vec<Vec<Edge>>,
+    heuristic: Vec<f32>,
+    goal: usize,
+}
+
+impl Graph {
+    pub fn build(nodes: usize, edges: &[(usize, usize, f32)], goal: usize) -> Self {
+        let mut adjacency = vec![vec![]; nodes];
+        let mut reverse_adjacency = vec![vec![]; nodes];
+        for &(from, to, cost) in edges {
+            adjacency[from].push(Edge { from, to, cost });
+            reverse_adjacency[to].push(Edge { from, to, cost });
+        }
+        Graph {
+            edges: adjacency,
+            reverse_edges: reverse_adjacency,
+            heuristic: vec![0.0; nodes],
+            goal,
+        }
+    }
+
+    pub fn with_heuristic(mut self, heuristic: Vec<f32>) -> Self {
+        self.heuristic = heuristic;
+        self
+    }
+}
+
+/// Position in a `Graph`. Two states are the same if they are in the same
+/// node, regardless of the cost of the path used to reach it.
+#[derive(Clone, Debug)]
+pub struct GraphState {
+    graph: Arc<Graph>,
+    pub node: usize,
+    pub cost: f32,
+}
+
+impl GraphState {
+    pub fn build(graph: Graph, node: usize) -> Self {
+        GraphState {
+            graph: Arc::new(graph),
+            node,
+            cost: 0.0,
+        }
+    }
+
+    /// State in another node of the same graph
+    pub fn with_node(&self, node: usize) -> Self {
+        GraphState {
+            graph: Arc::clone(&self.graph),
+            node,
+            cost: 0.0,
+        }
+    }
+}
+
+impl PartialEq for GraphState {
+    fn eq(&self, other: &Self) -> bool {
+        self.node == other.node
+    }
+}
+
+impl Eq for GraphState {}
+
+impl Hash for GraphState {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.node.hash(state);
+    }
+}
+
+impl State<Edge> for GraphState {
+    fn apply_action(&self, action: &Edge) -> Self {
+        GraphState {
+            graph: Arc::clone(&self.graph),
+            node: action.to,
+            cost: self.cost + action.cost(),
+        }
+    }
+
+    fn get_solution_cost(&self) -> Option<f32> {
+        Some(self.cost)
+    }
+
+    fn get_applicable_actions(&self) -> Vec<Edge> {
+        self.graph.edges[self.node].clone()
+    }
+
+    fn is_solution(&self) -> bool {
+        self.node == self.graph.goal
+    }
+}
+
+impl StateHeuristic<Edge> for GraphState {
+    fn heuristic(&self) -> f32 {
+        self.graph.heuristic[self.node]
+    }
+}
+
+impl PredecessorState<Edge> for GraphState {
+    fn get_predecessors(&self) -> Vec<(Self, Edge)> {
+        self.graph.reverse_edges[self.node]
+            .iter()
+            .map(|e| (self.with_node(e.from), *e))
+            .collect()
+    }
+}
+
+impl BidirectionalHeuristic<Edge> for GraphState {
+    /// Graph heuristic towards the goal, and no information towards any
+    /// other node
+    fn heuristic_to(&self, target: &Self) -> f32 {
+        match target.node == self.graph.goal {
+            true => self.heuristic(),
+            false => 0.0,
+        }
+    }
+}
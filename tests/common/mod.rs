@@ -1,8 +1,10 @@
+pub mod graph;
+
 use rustsearch::search::{Action, State, StateHeuristic};
 use std::cmp::{Ord, Ordering};
 use std::fmt::{Display, Formatter, Result};
 
-#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
 pub enum Operation {
     Sum,
     Sub,
@@ -10,7 +12,7 @@ pub enum Operation {
     Div,
 }
 
-#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
 pub struct NumberAction {
     n1: i32,
     n2: i32,
@@ -18,8 +20,10 @@ pub struct NumberAction {
 }
 
 impl Action for NumberAction {
-    fn cost(&self) -> f32 {
-        1.0
+    type Cost = u32;
+
+    fn cost(&self) -> u32 {
+        1
     }
 }
 
@@ -60,6 +64,7 @@ impl Display for NumberAction {
     }
 }
 
+#[allow(clippy::non_canonical_partial_ord_impl)]
 impl PartialOrd for NumberAction {
     fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
         match self.operation_result() {
@@ -76,30 +81,15 @@ impl Ord for NumberAction {
     }
 }
 
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
 pub struct NumberState {
     pub numbers: Vec<i32>,
     pub goal: i32,
-    pub actions: Vec<NumberAction>,
-}
-
-impl Clone for NumberState {
-    fn clone(&self) -> NumberState {
-        NumberState {
-            numbers: self.numbers.to_vec(),
-            actions: self.actions.to_vec(),
-            goal: self.goal,
-        }
-    }
 }
 
 impl NumberState {
     pub fn build(numbers: Vec<i32>, goal: i32) -> Self {
-        NumberState {
-            numbers,
-            goal,
-            actions: vec![],
-        }
+        NumberState { numbers, goal }
     }
 }
 
@@ -128,23 +118,10 @@ impl State<NumberAction> for NumberState {
 
         new_state.numbers.push(action.operation_result().unwrap());
         new_state.numbers.sort();
-        new_state.actions.push(*action);
-        new_state.actions.sort();
         new_state
     }
 
-    fn get_partial_solution(&self) -> Vec<NumberAction> {
-        self.actions.to_vec()
-    }
-
-    fn get_solution_cost(&self) -> f32 {
-        self.actions
-            .iter()
-            .map(|x| x.cost())
-            .reduce(|x, y| x + y)
-            .unwrap_or(0 as f32)
-    }
-
+    #[allow(clippy::useless_vec)]
     fn get_applicable_actions(&self) -> Vec<NumberAction> {
         let mut actions = vec![];
         for i1 in 0..(self.numbers.len() - 1) {
@@ -177,16 +154,12 @@ impl State<NumberAction> for NumberState {
     fn is_solution(&self) -> bool {
         self.numbers.contains(&self.goal)
     }
-
-    fn get_state_level(&self) -> usize {
-        self.actions.len()
-    }
 }
 
-impl StateHeuristic for NumberState {
+impl StateHeuristic<NumberAction> for NumberState {
     /// Heuristic that identifies as better state (lower value) the
     /// one with a number closed to the solution.
-    fn heuristic(&self) -> f32 {
+    fn heuristic(&self) -> u32 {
         let mindiff = self
             .numbers
             .iter()
@@ -194,6 +167,6 @@ impl StateHeuristic for NumberState {
             .reduce(std::cmp::min)
             .unwrap_or(self.goal);
 
-        (mindiff / self.goal) as f32
+        (mindiff / self.goal) as u32
     }
 }
@@ -1,7 +1,21 @@
 mod common;
 
-use rustsearch::search::Search;
+use std::cell::RefCell;
+use std::rc::Rc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
 
+use rustsearch::closedlist::ClosedList;
+use rustsearch::openlist::queue::Queue;
+use rustsearch::openlist::OpenList;
+use rustsearch::search::{
+    BeamOrder, DotExport, Event, Limit, Limits, Observer, ParallelSearch, Search, SearchError,
+    State, StateHeuristic, Statistics, TieBreak,
+};
+
+use crate::common::graph::{Edge, Graph, GraphState};
 use crate::common::{NumberAction, Operation};
 
 #[test]
@@ -9,10 +23,10 @@ fn one_step_breadth() {
     let initial_state = common::NumberState::build(vec![2, 4], 6);
     let expected_solutions = vec![NumberAction::build(2, 4, Operation::Sum)];
     let mut search = Search::new();
-    let solutions = search.search_breadth_all(initial_state);
+    let solutions = search.search_breadth_all(initial_state).unwrap();
     dbg!(&solutions);
     assert_eq!(1, solutions.len());
-    assert_eq!(expected_solutions, solutions[0]);
+    assert_eq!(expected_solutions, solutions[0].actions);
 }
 
 #[test]
@@ -20,10 +34,10 @@ fn one_step_depth() {
     let initial_state = common::NumberState::build(vec![2, 4], 6);
     let expected_solutions = vec![NumberAction::build(2, 4, Operation::Sum)];
     let mut search = Search::new();
-    let solutions = search.search_depth_all(initial_state);
+    let solutions = search.search_depth_all(initial_state).unwrap();
     dbg!(&solutions);
     assert_eq!(1, solutions.len());
-    assert_eq!(expected_solutions, solutions[0]);
+    assert_eq!(expected_solutions, solutions[0].actions);
 }
 
 #[test]
@@ -32,7 +46,7 @@ fn no_solution_breadth() {
     let mut search = Search::new();
     let solutions = search.search_breadth_all(initial_state);
     dbg!(&search.statistics);
-    assert!(solutions.is_empty());
+    assert_eq!(Ok(vec![]), solutions);
 }
 
 #[test]
@@ -41,7 +55,7 @@ fn no_solution_depth() {
     let mut search = Search::new();
     let solutions = search.search_depth_all(initial_state);
     dbg!(&search.statistics);
-    assert!(solutions.is_empty());
+    assert_eq!(Ok(vec![]), solutions);
 }
 
 #[test]
@@ -50,7 +64,7 @@ fn no_solution_iter_depth() {
     let mut search = Search::new();
     let solutions = search.search_iter_depth_first(initial_state, 1);
     dbg!(&search.statistics);
-    assert!(solutions.is_none());
+    assert_eq!(Ok(None), solutions);
 }
 
 #[test]
@@ -59,7 +73,16 @@ fn no_solution_a_start() {
     let mut search = Search::new();
     let solutions = search.search_a_start_first(initial_state);
     dbg!(&search.statistics);
-    assert!(solutions.is_none());
+    assert_eq!(Ok(None), solutions);
+}
+
+#[test]
+fn no_solution_ida_star() {
+    let initial_state = common::NumberState::build(vec![2, 4], 3);
+    let mut search = Search::new();
+    let solution = search.search_ida_star_first(initial_state);
+    dbg!(&search.statistics);
+    assert_eq!(Ok(None), solution);
 }
 
 #[test]
@@ -68,14 +91,14 @@ fn problem_1_breadth_first() {
     let mut search = Search::new();
     let solution = search.search_breadth_first(initial_state);
     dbg!(&search.statistics);
-    assert!(solution.is_some());
+    assert!(solution.unwrap().is_some());
 }
 
 #[test]
 fn problem_1_breadth_all() {
     let initial_state = common::NumberState::build(vec![2, 4, 5, 7, 25], 855);
     let mut search = Search::new();
-    let solutions = search.search_breadth_all(initial_state);
+    let solutions = search.search_breadth_all(initial_state).unwrap();
     dbg!(&search.statistics);
     assert_eq!(2, solutions.len());
 }
@@ -86,14 +109,14 @@ fn problem_1_depth_first() {
     let mut search = Search::new();
     let solution = search.search_depth_first(initial_state);
     dbg!(&search.statistics);
-    assert!(solution.is_some());
+    assert!(solution.unwrap().is_some());
 }
 
 #[test]
 fn problem_all_depth_first() {
     let initial_state = common::NumberState::build(vec![2, 4, 5, 7, 25], 855);
     let mut search = Search::new();
-    let solutions = search.search_depth_all(initial_state);
+    let solutions = search.search_depth_all(initial_state).unwrap();
     dbg!(&search.statistics);
     assert_eq!(2, solutions.len());
 }
@@ -104,7 +127,7 @@ fn problem_1_iter_depth() {
     let mut search = Search::new();
     let solution = search.search_iter_depth_first(initial_state, 1);
     dbg!(&search.statistics);
-    assert!(solution.is_some());
+    assert!(solution.unwrap().is_some());
 }
 
 #[test]
@@ -113,5 +136,1445 @@ fn problem_1_a_star_first() {
     let mut search = Search::new();
     let solution = search.search_a_start_first(initial_state);
     dbg!(&search.statistics);
-    assert!(solution.is_some());
+    assert!(solution.unwrap().is_some());
+}
+
+#[test]
+fn problem_1_breadth_all_hashed() {
+    let initial_state = common::NumberState::build(vec![2, 4, 5, 7, 25], 855);
+    let mut search = Search::new_hashed();
+    let solutions = search.search_breadth_all(initial_state).unwrap();
+    dbg!(&search.statistics);
+    assert_eq!(2, solutions.len());
+    assert!(search.statistics.duplicates > 0);
+}
+
+#[test]
+fn problem_1_hashed_matches_linear() {
+    let initial_state = common::NumberState::build(vec![2, 4, 5, 7, 25], 855);
+    let mut linear = Search::new();
+    let mut hashed = Search::new_hashed();
+    let linear_solution = linear.search_a_start_first(initial_state.clone());
+    let hashed_solution = hashed.search_a_start_first(initial_state);
+    assert_eq!(linear_solution, hashed_solution);
+    assert_eq!(
+        linear.statistics.nodes_explored,
+        hashed.statistics.nodes_explored
+    );
+    assert_eq!(linear.statistics.duplicates, hashed.statistics.duplicates);
+}
+
+#[test]
+fn problem_1_a_star_fifo_tie_break() {
+    let initial_state = common::NumberState::build(vec![2, 4, 5, 7, 25], 855);
+    let mut search = Search::new_hashed();
+    search.set_tie_break(TieBreak::Fifo);
+    let solution = search.search_a_start_first(initial_state).unwrap().unwrap();
+    dbg!(&search.statistics);
+    assert_eq!(3, solution.depth);
+}
+
+/// Graph where the shallowest path to the goal (0 -> 1 -> 3, cost 11) is
+/// not the cheapest one (0 -> 2 -> 4 -> 3, cost 6)
+fn weighted_graph() -> Graph {
+    Graph::build(
+        5,
+        &[
+            (0, 1, 1.0),
+            (1, 3, 10.0),
+            (0, 2, 2.0),
+            (2, 4, 2.0),
+            (4, 3, 2.0),
+        ],
+        3,
+    )
+}
+
+#[test]
+fn weighted_graph_breadth_first() {
+    let initial_state = GraphState::build(weighted_graph(), 0);
+    let mut search = Search::new_hashed();
+    let solution = search.search_breadth_first(initial_state).unwrap().unwrap();
+    assert_eq!(2, solution.depth);
+    assert_eq!(11.0, solution.cost);
+}
+
+#[test]
+fn weighted_graph_uniform_cost_first() {
+    let initial_state = GraphState::build(weighted_graph(), 0);
+    let mut search = Search::new_hashed();
+    let solution = search
+        .search_uniform_cost_first(initial_state)
+        .unwrap()
+        .unwrap();
+    dbg!(&search.statistics);
+    assert_eq!(
+        vec![2, 4, 3],
+        solution.actions.iter().map(|e| e.to).collect::<Vec<_>>()
+    );
+}
+
+#[test]
+fn weighted_graph_solution() {
+    let initial_state = GraphState::build(weighted_graph(), 0);
+    let mut search = Search::new_hashed();
+    search.search_breadth_all(initial_state.clone()).unwrap();
+    let solution = search
+        .search_uniform_cost_first(initial_state)
+        .unwrap()
+        .unwrap();
+    assert_eq!(6.0, solution.cost);
+    assert_eq!(3, solution.depth);
+    assert_eq!(
+        vec![0, 2, 4, 3],
+        solution.states.iter().map(|s| s.node).collect::<Vec<_>>()
+    );
+    // counted from the start of the uniform cost search
+    assert_eq!(5, solution.nodes_explored);
+    assert_eq!(solution.actions.clone(), Vec::from(solution));
+}
+
+#[test]
+fn problem_1_uniform_cost_all() {
+    let initial_state = common::NumberState::build(vec![2, 4, 5, 7, 25], 855);
+    let mut search = Search::new_hashed();
+    let solutions = search.search_uniform_cost_all(initial_state).unwrap();
+    dbg!(&search.statistics);
+    assert_eq!(2, solutions.len());
+    assert!(solutions.windows(2).all(|w| w[0].cost <= w[1].cost));
+}
+
+#[test]
+fn weighted_graph_greedy_first() {
+    let graph = weighted_graph().with_heuristic(vec![3.0, 1.0, 4.0, 0.0, 2.0]);
+    let initial_state = GraphState::build(graph, 0);
+    let mut search = Search::new_hashed();
+    let solution = search.search_greedy_first(initial_state).unwrap().unwrap();
+    dbg!(&search.statistics);
+    assert_eq!(
+        vec![1, 3],
+        solution.actions.iter().map(|e| e.to).collect::<Vec<_>>()
+    );
+    assert_eq!(3, search.statistics.nodes_explored);
+}
+
+#[test]
+fn problem_1_greedy_first() {
+    let initial_state = common::NumberState::build(vec![2, 4, 5, 7, 25], 855);
+    let mut search = Search::new_hashed();
+    let solution = search.search_greedy_first(initial_state);
+    dbg!(&search.statistics);
+    assert!(solution.unwrap().is_some());
+    assert!(search.statistics.nodes_explored > 0);
+}
+
+#[test]
+fn weighted_graph_weighted_a_star() {
+    let heuristic = vec![3.0, 1.0, 4.0, 0.0, 2.0];
+    let initial_state = GraphState::build(weighted_graph().with_heuristic(heuristic.clone()), 0);
+    let mut search = Search::new_hashed();
+    let solution = search
+        .search_weighted_a_star_first(initial_state, 1.0)
+        .unwrap()
+        .unwrap();
+    assert_eq!(1.0, solution.bound);
+    assert_eq!(
+        vec![2, 4, 3],
+        solution
+            .solution
+            .actions
+            .iter()
+            .map(|e| e.to)
+            .collect::<Vec<_>>()
+    );
+
+    let initial_state = GraphState::build(weighted_graph().with_heuristic(heuristic), 0);
+    let mut search = Search::new_hashed();
+    let solution = search
+        .search_weighted_a_star_first(initial_state, 5.0)
+        .unwrap()
+        .unwrap();
+    assert_eq!(5.0, solution.bound);
+    assert!(solution.solution.cost <= solution.bound * 6.0);
+}
+
+#[test]
+fn problem_1_weighted_a_star_integer_cost() {
+    let initial_state = common::NumberState::build(vec![2, 4, 5, 7, 25], 855);
+    let mut search = Search::new_hashed();
+    let solution = search
+        .search_weighted_a_star_first(initial_state, 2.5)
+        .unwrap()
+        .unwrap();
+    // integer costs: the weighted heuristic is rounded down
+    assert_eq!(solution.solution.depth as u32, solution.solution.cost);
+    assert!(solution.solution.cost <= 3 * 5 / 2);
+}
+
+#[test]
+fn weighted_a_star_invalid_weight() {
+    let initial_state = GraphState::build(weighted_graph(), 0);
+    let mut search = Search::new_hashed();
+    search.search_a_start_first(initial_state.clone()).unwrap();
+    let error = search
+        .search_weighted_a_star_first(initial_state, 0.5)
+        .unwrap_err();
+    assert!(matches!(error, SearchError::InvalidParameter { .. }));
+    assert_eq!(
+        "weighted_a_star: invalid parameter, the weight must be >= 1",
+        error.to_string()
+    );
+    // the report is not left with the previous run
+    assert_eq!("weighted_a_star", search.report().algorithm);
+    assert_eq!(0, search.statistics.nodes_explored);
+}
+
+#[test]
+fn problem_1_ida_star_first() {
+    let initial_state = common::NumberState::build(vec![2, 4, 5, 7, 25], 855);
+    let mut search = Search::new();
+    let solution = search
+        .search_ida_star_first(initial_state)
+        .unwrap()
+        .unwrap();
+    dbg!(&search.statistics);
+    assert_eq!(3, solution.depth);
+    // accumulated from `Action::cost`, the state does not keep it
+    assert_eq!(3, solution.cost);
+}
+
+#[test]
+fn weighted_graph_ida_star_first() {
+    // cycle between 1 and 0, which the path check has to skip
+    let graph = Graph::build(
+        5,
+        &[
+            (0, 1, 1.0),
+            (1, 0, 1.0),
+            (1, 3, 10.0),
+            (0, 2, 2.0),
+            (2, 4, 2.0),
+            (4, 3, 2.0),
+        ],
+        3,
+    )
+    .with_heuristic(vec![3.0, 1.0, 4.0, 0.0, 2.0]);
+    let initial_state = GraphState::build(graph, 0);
+    let mut search = Search::new();
+    let solution = search
+        .search_ida_star_first(initial_state)
+        .unwrap()
+        .unwrap();
+    dbg!(&search.statistics);
+    assert_eq!(
+        vec![2, 4, 3],
+        solution.actions.iter().map(|e| e.to).collect::<Vec<_>>()
+    );
+    assert!(search.statistics.duplicates > 0);
+}
+
+#[test]
+fn problem_1_a_star_all() {
+    let initial_state = common::NumberState::build(vec![2, 4, 5, 7, 25], 855);
+    let mut search = Search::new_hashed();
+    let solutions = search.search_a_star_all(initial_state).unwrap();
+    dbg!(&search.statistics);
+    assert_eq!(2, solutions.len());
+    assert!(solutions.windows(2).all(|w| w[0].cost <= w[1].cost));
+}
+
+#[test]
+fn weighted_graph_a_star_k_best() {
+    let initial_state = GraphState::build(weighted_graph(), 0);
+    let mut search = Search::new_hashed();
+    let solutions = search.search_a_star_k_best(initial_state, 5).unwrap();
+    assert_eq!(
+        vec![6.0, 11.0],
+        solutions.iter().map(|s| s.cost).collect::<Vec<_>>()
+    );
+    assert_eq!(
+        vec![vec![2, 4, 3], vec![1, 3]],
+        solutions
+            .iter()
+            .map(|s| s.actions.iter().map(|e| e.to).collect::<Vec<_>>())
+            .collect::<Vec<_>>()
+    );
+}
+
+#[test]
+fn problem_1_a_star_k_best() {
+    let initial_state = common::NumberState::build(vec![2, 4, 5, 7, 25], 855);
+    let mut search = Search::new_hashed();
+    let solutions = search
+        .search_a_star_k_best(initial_state.clone(), 1)
+        .unwrap();
+    assert_eq!(1, solutions.len());
+
+    let mut search = Search::new_hashed();
+    let solutions = search
+        .search_a_star_k_best(initial_state.clone(), 5)
+        .unwrap();
+    // plans through the same states are returned too
+    assert_eq!(4, solutions.len());
+    assert!(solutions.windows(2).all(|w| w[0].cost <= w[1].cost));
+
+    let mut search = Search::new_hashed();
+    let solutions = search.search_a_star_k_best(initial_state, 0);
+    assert_eq!(Ok(vec![]), solutions);
+    assert_eq!(0, search.statistics.nodes_explored);
+}
+
+/// Admissible but inconsistent heuristic: `h(1) = 3` is greater than
+/// `c(1, 2) + h(2) = 1`, so node 2 is first closed through the expensive
+/// edge 0 -> 2 and has to be reopened when reached through 1.
+fn inconsistent_graph() -> Graph {
+    Graph::build(4, &[(0, 1, 1.0), (0, 2, 3.0), (1, 2, 1.0), (2, 3, 3.0)], 3)
+        .with_heuristic(vec![0.0, 3.0, 0.0, 0.0])
+}
+
+#[test]
+fn inconsistent_heuristic_a_star_reopening() {
+    let initial_state = GraphState::build(inconsistent_graph(), 0);
+    let mut search = Search::new_hashed();
+    let solution = search.search_a_start_first(initial_state).unwrap().unwrap();
+    dbg!(&search.statistics);
+    assert_eq!(
+        vec![1, 2, 3],
+        solution.actions.iter().map(|e| e.to).collect::<Vec<_>>()
+    );
+    assert_eq!(1, search.statistics.reopened);
+}
+
+#[test]
+fn inconsistent_heuristic_a_star_reopening_linear() {
+    let initial_state = GraphState::build(inconsistent_graph(), 0);
+    let mut search = Search::new();
+    let solution = search.search_a_start_first(initial_state).unwrap().unwrap();
+    assert_eq!(5.0, solution.cost);
+    assert_eq!(1, search.statistics.reopened);
+}
+
+/// Grid of `size * size` nodes joined in both directions, with a cheap
+/// detour along the borders, goal at the opposite corner
+fn grid_graph(size: usize) -> Graph {
+    let mut edges = vec![];
+    for row in 0..size {
+        for column in 0..size {
+            let node = row * size + column;
+            let border = row == 0 || column == size - 1;
+            let cost = if border { 1.0 } else { 3.0 };
+            if column + 1 < size {
+                edges.push((node, node + 1, cost));
+                edges.push((node + 1, node, cost));
+            }
+            if row + 1 < size {
+                edges.push((node, node + size, cost));
+                edges.push((node + size, node, cost));
+            }
+        }
+    }
+    let heuristic = (0..size * size)
+        .map(|node| ((size - 1 - node / size) + (size - 1 - node % size)) as f32)
+        .collect();
+    Graph::build(size * size, &edges, size * size - 1).with_heuristic(heuristic)
+}
+
+#[test]
+fn grid_bidirectional_breadth_first() {
+    let initial_state = GraphState::build(grid_graph(6), 0);
+    let goal_state = initial_state.with_node(35);
+    let mut search = Search::new_hashed();
+    let solution = search
+        .search_bidirectional_breadth_first(initial_state, goal_state)
+        .unwrap()
+        .unwrap()
+        .into_actions();
+    dbg!(&search.statistics);
+    assert_eq!(10, solution.len());
+    assert_eq!(0, solution[0].from);
+    assert_eq!(35, solution[9].to);
+    assert!(solution.windows(2).all(|w| w[0].to == w[1].from));
+}
+
+#[test]
+fn grid_bidirectional_heuristic_first() {
+    let initial_state = GraphState::build(grid_graph(6), 0);
+    let goal_state = initial_state.with_node(35);
+    let mut search = Search::new_hashed();
+    let solution = search
+        .search_bidirectional_heuristic_first(initial_state.clone(), goal_state)
+        .unwrap()
+        .unwrap();
+    dbg!(&search.statistics);
+    let cost: f32 = solution.actions.iter().map(|e| e.cost).sum();
+    assert_eq!(cost, solution.cost);
+    assert!(solution.actions.windows(2).all(|w| w[0].to == w[1].from));
+    assert_eq!(35, solution.solution_state().node);
+    assert!(solution
+        .states
+        .iter()
+        .zip(&solution.actions)
+        .all(|(state, action)| state.node == action.from));
+
+    let mut search = Search::new_hashed();
+    let expected = search
+        .search_uniform_cost_first(initial_state)
+        .unwrap()
+        .unwrap();
+    assert_eq!(expected.cost, cost);
+    assert_eq!(10.0, cost);
+}
+
+#[test]
+fn bidirectional_same_state() {
+    let initial_state = GraphState::build(grid_graph(3), 4);
+    let goal_state = initial_state.with_node(4);
+    let mut search = Search::new_hashed();
+    let solution = search
+        .search_bidirectional_breadth_first(initial_state, goal_state)
+        .unwrap()
+        .unwrap();
+    assert!(solution.actions.is_empty());
+    assert_eq!(4, solution.solution_state().node);
+}
+
+#[test]
+fn bidirectional_no_solution() {
+    let graph = Graph::build(4, &[(0, 1, 1.0), (2, 3, 1.0)], 3);
+    let initial_state = GraphState::build(graph, 0);
+    let goal_state = initial_state.with_node(3);
+    let mut search = Search::new_hashed();
+    assert_eq!(
+        Ok(None),
+        search.search_bidirectional_breadth_first(initial_state.clone(), goal_state.clone())
+    );
+    assert_eq!(
+        Ok(None),
+        search.search_bidirectional_heuristic_first(initial_state, goal_state)
+    );
+}
+
+#[test]
+fn grid_beam_first() {
+    let initial_state = GraphState::build(grid_graph(6), 0);
+    let mut search = Search::new_hashed();
+    let solution = search
+        .search_beam_first(initial_state, 2, BeamOrder::Heuristic)
+        .unwrap()
+        .unwrap();
+    dbg!(&search.statistics);
+    assert_eq!(10, solution.depth);
+    assert!(search.statistics.pruned_by_layer.iter().sum::<usize>() > 0);
+    assert_eq!(0, search.statistics.pruned_by_layer[0]);
+}
+
+#[test]
+fn grid_beam_first_cost_and_heuristic() {
+    let initial_state = GraphState::build(grid_graph(6), 0);
+    let mut search = Search::new_hashed();
+    let solution = search
+        .search_beam_first(initial_state, 2, BeamOrder::CostAndHeuristic)
+        .unwrap()
+        .unwrap();
+    dbg!(&search.statistics);
+    assert_eq!(10.0, solution.cost);
+}
+
+#[test]
+fn weighted_graph_beam_pruned() {
+    // a width of 1 follows the best heuristic value into the dead end 1 -> 5
+    let graph = Graph::build(6, &[(0, 1, 1.0), (0, 2, 1.0), (1, 5, 1.0), (2, 3, 1.0)], 3)
+        .with_heuristic(vec![2.0, 0.5, 1.0, 0.0, 0.0, 1.0]);
+    let initial_state = GraphState::build(graph, 0);
+    let mut search = Search::new_hashed();
+    let solution = search.search_beam_first(initial_state, 1, BeamOrder::Heuristic);
+    dbg!(&search.statistics);
+    assert_eq!(Ok(None), solution);
+    assert_eq!(vec![0, 1, 0], search.statistics.pruned_by_layer);
+}
+
+#[test]
+fn problem_1_beam_first() {
+    let initial_state = common::NumberState::build(vec![2, 4, 5, 7, 25], 855);
+    let mut search = Search::new_hashed();
+    let solution = search.search_beam_first(initial_state, 5000, BeamOrder::Heuristic);
+    dbg!(&search.statistics);
+    assert!(solution.unwrap().is_some());
+}
+
+#[test]
+fn problem_1_breadth_node_limit() {
+    let initial_state = common::NumberState::build(vec![2, 4, 5, 7, 25], 855);
+    let mut search = Search::new_hashed();
+    search.set_limits(Limits {
+        max_nodes: Some(10),
+        ..Limits::default()
+    });
+    let solution = search.search_breadth_first(initial_state);
+    dbg!(&search.statistics);
+    assert_eq!(
+        Err(SearchError::LimitReached {
+            algorithm: "breadth_first",
+            limit: Limit::Nodes,
+            partial: ()
+        }),
+        solution
+    );
+    assert_eq!(10, search.statistics.nodes_explored);
+}
+
+#[test]
+fn problem_1_iter_depth_node_limit() {
+    let initial_state = common::NumberState::build(vec![2, 4, 5, 7, 25], 855);
+    let mut search = Search::new_hashed();
+    search.set_limits(Limits {
+        max_nodes: Some(100),
+        ..Limits::default()
+    });
+    let solution = search.search_iter_depth_first(initial_state, 1);
+    assert_eq!(
+        Err(SearchError::LimitReached {
+            algorithm: "iterative_deepening",
+            limit: Limit::Nodes,
+            partial: ()
+        }),
+        solution
+    );
+    assert_eq!(100, search.statistics.nodes_explored);
+}
+
+#[test]
+fn problem_1_a_star_memory_limit() {
+    let initial_state = common::NumberState::build(vec![2, 4, 5, 7, 25], 855);
+    let mut search = Search::new_hashed();
+    search.set_limits(Limits {
+        max_stored: Some(50),
+        ..Limits::default()
+    });
+    let solution = search.search_a_start_first(initial_state);
+    assert_eq!(
+        Err(SearchError::LimitReached {
+            algorithm: "a_star",
+            limit: Limit::Memory,
+            partial: ()
+        }),
+        solution
+    );
+}
+
+#[test]
+fn problem_1_depth_time_limit() {
+    let initial_state = common::NumberState::build(vec![2, 4, 5, 7, 25], 855);
+    let mut search = Search::new_hashed();
+    search.set_limits(Limits {
+        max_time: Some(Duration::ZERO),
+        ..Limits::default()
+    });
+    let solutions = search.search_depth_all(initial_state);
+    assert_eq!(
+        Err(SearchError::LimitReached {
+            algorithm: "depth_first",
+            limit: Limit::Time,
+            partial: vec![]
+        }),
+        solutions
+    );
+}
+
+#[test]
+fn problem_1_a_star_k_best_node_limit() {
+    let initial_state = common::NumberState::build(vec![2, 4, 5, 7, 25], 855);
+    let mut search = Search::new_hashed();
+    search.set_limits(Limits {
+        max_nodes: Some(6317),
+        ..Limits::default()
+    });
+    let error = search.search_a_star_k_best(initial_state, 5).unwrap_err();
+    assert!(matches!(
+        error,
+        SearchError::LimitReached {
+            limit: Limit::Nodes,
+            ..
+        }
+    ));
+    // the plans found before the limit are kept, still ranked
+    let partial = error.into_partial().unwrap();
+    assert_eq!(search.statistics.solutions, partial.len());
+    assert_eq!(3, partial.len());
+    assert!(partial.windows(2).all(|w| w[0].cost <= w[1].cost));
+}
+
+#[test]
+fn limits_are_per_run() {
+    let mut search = Search::new_hashed();
+    search.set_limits(Limits {
+        max_nodes: Some(10),
+        ..Limits::default()
+    });
+    let initial_state = GraphState::build(weighted_graph(), 0);
+    assert!(search
+        .search_uniform_cost_first(initial_state)
+        .unwrap()
+        .is_some());
+    let initial_state = GraphState::build(grid_graph(3), 0);
+    assert!(search
+        .search_a_start_first(initial_state)
+        .unwrap()
+        .is_some());
+}
+
+#[test]
+fn cancelled_before_start() {
+    let initial_state = common::NumberState::build(vec![2, 4, 5, 7, 25], 855);
+    let mut search = Search::new_hashed();
+    search.set_cancel_flag(Arc::new(AtomicBool::new(true)));
+    let solution = search.search_breadth_first(initial_state);
+    assert_eq!(
+        Err(SearchError::Cancelled {
+            algorithm: "breadth_first",
+            partial: ()
+        }),
+        solution
+    );
+    assert_eq!(0, search.statistics.nodes_explored);
+    assert!(search.statistics.elapsed > Duration::ZERO);
+}
+
+#[test]
+fn cancelled_from_progress() {
+    let initial_state = common::NumberState::build(vec![2, 4, 5, 7, 25], 855);
+    let cancel = Arc::new(AtomicBool::new(false));
+    let reports = Rc::new(RefCell::new(vec![]));
+    let mut search = Search::new_hashed();
+    search.set_cancel_flag(Arc::clone(&cancel));
+    let progress_reports = Rc::clone(&reports);
+    search.set_progress(10, move |progress| {
+        progress_reports.borrow_mut().push((
+            progress.statistics.nodes_explored,
+            progress.frontier,
+            progress.best_f,
+        ));
+        if progress.statistics.nodes_explored == 30 {
+            cancel.store(true, Ordering::Relaxed);
+        }
+    });
+    let solution = search.search_a_start_first(initial_state);
+    assert_eq!(
+        Err(SearchError::Cancelled {
+            algorithm: "a_star",
+            partial: ()
+        }),
+        solution
+    );
+    assert_eq!(30, search.statistics.nodes_explored);
+
+    let reports = reports.borrow();
+    assert_eq!(
+        vec![10, 20, 30],
+        reports.iter().map(|r| r.0).collect::<Vec<_>>()
+    );
+    assert!(reports.iter().all(|r| r.1 > 0 && r.2.is_some()));
+}
+
+#[test]
+fn cancelled_from_other_thread() {
+    let cancel = Arc::new(AtomicBool::new(false));
+    let worker_cancel = Arc::clone(&cancel);
+    let worker = thread::spawn(move || {
+        // enough numbers to not finish before being cancelled
+        let initial_state =
+            common::NumberState::build(vec![2, 3, 4, 5, 7, 11, 13, 17, 25], 1_000_000);
+        let mut search = Search::new_hashed();
+        search.set_cancel_flag(worker_cancel);
+        matches!(
+            search.search_breadth_all(initial_state),
+            Err(SearchError::Cancelled { .. })
+        )
+    });
+    thread::sleep(Duration::from_millis(50));
+    cancel.store(true, Ordering::Relaxed);
+    assert!(worker.join().unwrap());
+}
+
+/// Cancels the search from its progress callback after 5 expansions
+fn cancel_after_5<C: ClosedList<Rc<GraphState>, f32>>(search: &mut Search<GraphState, Edge, C>) {
+    let cancel = Arc::new(AtomicBool::new(false));
+    search.set_cancel_flag(Arc::clone(&cancel));
+    search.set_progress(5, move |_| cancel.store(true, Ordering::Relaxed));
+}
+
+#[test]
+fn cancelled_ida_star() {
+    let initial_state = GraphState::build(grid_graph(6), 0);
+    let mut search = Search::new_hashed();
+    cancel_after_5(&mut search);
+    assert_eq!(
+        Err(SearchError::Cancelled {
+            algorithm: "ida_star",
+            partial: ()
+        }),
+        search.search_ida_star_first(initial_state)
+    );
+    assert_eq!(5, search.statistics.nodes_explored);
+}
+
+#[test]
+fn cancelled_beam() {
+    let initial_state = GraphState::build(grid_graph(6), 0);
+    let mut search = Search::new_hashed();
+    cancel_after_5(&mut search);
+    assert_eq!(
+        Err(SearchError::Cancelled {
+            algorithm: "beam",
+            partial: ()
+        }),
+        search.search_beam_first(initial_state, 2, BeamOrder::Heuristic)
+    );
+    assert_eq!(5, search.statistics.nodes_explored);
+}
+
+#[test]
+fn cancelled_bidirectional() {
+    let initial_state = GraphState::build(grid_graph(6), 0);
+    let mut search = Search::new_hashed();
+    cancel_after_5(&mut search);
+    let goal_state = initial_state.with_node(35);
+    assert_eq!(
+        Err(SearchError::Cancelled {
+            algorithm: "bidirectional_breadth_first",
+            partial: ()
+        }),
+        search.search_bidirectional_breadth_first(initial_state, goal_state)
+    );
+    assert_eq!(5, search.statistics.nodes_explored);
+
+    let initial_state = GraphState::build(grid_graph(6), 0);
+    let mut search = Search::new_hashed();
+    cancel_after_5(&mut search);
+    let goal_state = initial_state.with_node(35);
+    assert_eq!(
+        Err(SearchError::Cancelled {
+            algorithm: "bidirectional_heuristic",
+            partial: ()
+        }),
+        search.search_bidirectional_heuristic_first(initial_state, goal_state)
+    );
+    assert_eq!(5, search.statistics.nodes_explored);
+}
+
+#[test]
+fn progress_breadth_without_f() {
+    let initial_state = GraphState::build(grid_graph(4), 0);
+    let best_f = Rc::new(RefCell::new(vec![]));
+    let mut search = Search::new_hashed();
+    let progress_best_f = Rc::clone(&best_f);
+    search.set_progress(1, move |progress| {
+        progress_best_f.borrow_mut().push(progress.best_f)
+    });
+    assert!(search
+        .search_breadth_first(initial_state)
+        .unwrap()
+        .is_some());
+    assert_eq!(search.statistics.nodes_explored, best_f.borrow().len());
+    assert!(best_f.borrow().iter().all(|f| f.is_none()));
+}
+
+#[test]
+fn problem_1_iter_breadth() {
+    let initial_state = common::NumberState::build(vec![2, 4, 5, 7, 25], 855);
+    let mut search = Search::new_hashed();
+    let mut solutions = search.iter_breadth(initial_state.clone());
+    assert!(solutions.next().unwrap().is_ok());
+    let nodes_first = solutions.search().statistics.nodes_explored;
+    assert!(solutions.next().unwrap().is_ok());
+    assert!(solutions.search().statistics.nodes_explored > nodes_first);
+    assert!(solutions.next().is_none());
+
+    let mut all_search = Search::new_hashed();
+    let all = all_search
+        .search_breadth_all(initial_state.clone())
+        .unwrap();
+    let mut search = Search::new_hashed();
+    let lazy: Vec<_> = search
+        .iter_breadth(initial_state)
+        .collect::<Result<_, _>>()
+        .unwrap();
+    assert_eq!(all, lazy);
+}
+
+#[test]
+fn problem_1_iter_depth_take() {
+    let initial_state = common::NumberState::build(vec![2, 4, 5, 7, 25], 855);
+    let mut search = Search::new_hashed();
+    let solutions: Vec<_> = search.iter_depth(initial_state).take(1).collect();
+    assert_eq!(1, solutions.len());
+    assert_eq!(1, search.statistics.solutions);
+}
+
+#[test]
+fn iter_breadth_limit() {
+    let initial_state = common::NumberState::build(vec![2, 4, 5, 7, 25], 855);
+    let mut search = Search::new_hashed();
+    search.set_limits(Limits {
+        max_nodes: Some(10),
+        ..Limits::default()
+    });
+    let mut solutions = search.iter_breadth(initial_state);
+    assert_eq!(
+        Some(Err(SearchError::LimitReached {
+            algorithm: "breadth_first",
+            limit: Limit::Nodes,
+            partial: ()
+        })),
+        solutions.next()
+    );
+    assert!(solutions.next().is_none());
+}
+
+#[test]
+fn long_chain_depth_first() {
+    // plans are rebuilt from the parent links of the search nodes, which
+    // must also be released without overflowing the stack
+    let nodes = 200_000;
+    let edges: Vec<_> = (1..nodes).map(|node| (node - 1, node, 1.0)).collect();
+    let initial_state = GraphState::build(Graph::build(nodes, &edges, nodes - 1), 0);
+    let mut search = Search::new_hashed();
+    let solution: Vec<_> = search
+        .search_depth_first(initial_state)
+        .unwrap()
+        .unwrap()
+        .into();
+    assert_eq!(nodes - 1, solution.len());
+    assert!(solution.windows(2).all(|w| w[0].to == w[1].from));
+    assert_eq!(nodes - 1, search.statistics.max_depth);
+}
+
+/// Graph state that reports twice the real cost of its path
+#[derive(Clone, Debug, PartialEq)]
+struct DoubledCost(GraphState);
+
+impl State<Edge> for DoubledCost {
+    fn apply_action(&self, action: &Edge) -> Self {
+        DoubledCost(self.0.apply_action(action))
+    }
+
+    fn get_solution_cost(&self) -> Option<f32> {
+        self.0.get_solution_cost().map(|cost| 2.0 * cost)
+    }
+
+    fn get_applicable_actions(&self) -> Vec<Edge> {
+        self.0.get_applicable_actions()
+    }
+
+    fn is_solution(&self) -> bool {
+        self.0.is_solution()
+    }
+}
+
+#[test]
+#[cfg(debug_assertions)]
+fn disagreeing_solution_cost() {
+    let initial_state = DoubledCost(GraphState::build(weighted_graph(), 0));
+    let mut search = Search::new();
+    let error = search.search_uniform_cost_first(initial_state).unwrap_err();
+    assert!(
+        matches!(&error, SearchError::Panicked { message, .. } if message.contains("solution cost"))
+    );
+}
+
+/// Graph state where leaving node 2 is five times more expensive
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+struct Congested(GraphState);
+
+impl State<Edge> for Congested {
+    fn apply_action(&self, action: &Edge) -> Self {
+        Congested(self.0.apply_action(action))
+    }
+
+    fn get_applicable_actions(&self) -> Vec<Edge> {
+        self.0.get_applicable_actions()
+    }
+
+    fn is_solution(&self) -> bool {
+        self.0.is_solution()
+    }
+
+    fn action_cost(&self, action: &Edge) -> f32 {
+        match self.0.node {
+            2 => 5.0 * action.cost,
+            _ => action.cost,
+        }
+    }
+}
+
+#[test]
+fn state_dependent_cost_uniform_cost_first() {
+    // 0 -> 2 -> 4 -> 3 now costs 14, more than 0 -> 1 -> 3
+    let initial_state = Congested(GraphState::build(weighted_graph(), 0));
+    let mut search = Search::new_hashed();
+    let solution = search
+        .search_uniform_cost_first(initial_state)
+        .unwrap()
+        .unwrap();
+    assert_eq!(11.0, solution.cost);
+    assert_eq!(
+        vec![1, 3],
+        solution.actions.iter().map(|e| e.to).collect::<Vec<_>>()
+    );
+}
+
+#[test]
+fn weighted_graph_breadth_statistics() {
+    let initial_state = GraphState::build(weighted_graph(), 0);
+    let mut search = Search::new_hashed();
+    assert!(search
+        .search_breadth_first(initial_state)
+        .unwrap()
+        .is_some());
+    assert_eq!(4, search.statistics.nodes_explored);
+    assert_eq!(4, search.statistics.nodes_generated);
+    assert_eq!(2, search.statistics.max_open);
+    assert_eq!(vec![1, 2, 1], search.statistics.expanded_by_depth);
+    assert!(search.statistics.elapsed > Duration::ZERO);
+}
+
+#[test]
+fn statistics_are_per_run() {
+    let initial_state = common::NumberState::build(vec![2, 4, 5, 7, 25], 855);
+    let mut search = Search::new_hashed();
+    search.search_breadth_all(initial_state.clone()).unwrap();
+    let first = search.statistics.clone();
+    search.search_breadth_all(initial_state).unwrap();
+    assert_eq!(first.nodes_explored, search.statistics.nodes_explored);
+    assert_eq!(first.nodes_generated, search.statistics.nodes_generated);
+    assert_eq!(first.expanded_by_depth, search.statistics.expanded_by_depth);
+    assert_eq!(
+        first.nodes_explored,
+        first.expanded_by_depth.iter().sum::<usize>()
+    );
+}
+
+#[test]
+fn effective_branching_factor() {
+    let statistics = Statistics {
+        nodes_generated: 14,
+        max_depth: 3,
+        ..Statistics::default()
+    };
+    assert!((statistics.effective_branching_factor() - 2.0).abs() < 1e-6);
+    assert_eq!(0.0, Statistics::default().effective_branching_factor());
+}
+
+#[test]
+fn report_json() {
+    let initial_state = GraphState::build(weighted_graph(), 0);
+    let mut search = Search::new_hashed();
+    search.set_limits(Limits {
+        max_nodes: Some(100),
+        ..Limits::default()
+    });
+    search.search_breadth_first(initial_state).unwrap();
+    let json = search.report().to_json();
+    assert!(json.starts_with("{\"algorithm\":\"breadth_first\",\"limits\":{"));
+    assert!(json.contains("\"max_nodes\":100,\"max_time\":null,\"max_stored\":null"));
+    assert!(json.contains("\"nodes_explored\":4,\"nodes_generated\":4"));
+    assert!(json.contains("\"expanded_by_depth\":[1,2,1],\"pruned_by_layer\":[]"));
+    assert!(json.ends_with("}}"));
+}
+
+#[test]
+fn report_csv_append() {
+    let path = std::env::temp_dir().join(format!("rustsearch-report-{}.csv", std::process::id()));
+    let _ = std::fs::remove_file(&path);
+
+    let initial_state = GraphState::build(weighted_graph(), 0);
+    let mut search = Search::new_hashed();
+    search.search_breadth_first(initial_state.clone()).unwrap();
+    search.report().append_csv(&path).unwrap();
+    search.search_a_start_first(initial_state).unwrap();
+    search.report().append_csv(&path).unwrap();
+
+    let csv = std::fs::read_to_string(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+    let lines: Vec<_> = csv.lines().collect();
+    assert_eq!(3, lines.len());
+    assert_eq!(rustsearch::search::Report::csv_header(), lines[0]);
+    assert!(lines[1].starts_with("breadth_first,,,,4,4,2,1,"));
+    assert!(lines[2].starts_with("a_star,"));
+    let columns = lines[0].split(',').count();
+    assert!(lines.iter().all(|line| line.split(',').count() == columns));
+}
+
+/// Counts the events of each hook, with the heuristic values seen
+#[derive(Debug, Default)]
+struct CountingObserver {
+    expanded: usize,
+    generated: usize,
+    duplicates: usize,
+    cutoffs: usize,
+    solutions: Vec<f32>,
+    heuristics: Vec<Option<f32>>,
+}
+
+impl Observer<GraphState, Edge> for CountingObserver {
+    fn expanded(&mut self, event: &Event<GraphState, Edge>) {
+        self.expanded += 1;
+        self.heuristics.push(event.h);
+    }
+
+    fn generated(&mut self, _event: &Event<GraphState, Edge>) {
+        self.generated += 1;
+    }
+
+    fn duplicate(&mut self, _event: &Event<GraphState, Edge>) {
+        self.duplicates += 1;
+    }
+
+    fn depth_cutoff(&mut self, _event: &Event<GraphState, Edge>) {
+        self.cutoffs += 1;
+    }
+
+    fn solution(&mut self, event: &Event<GraphState, Edge>) {
+        self.solutions.push(event.g);
+    }
+}
+
+fn assert_matches_statistics(observer: &CountingObserver, statistics: &Statistics) {
+    assert_eq!(statistics.nodes_explored, observer.expanded);
+    assert_eq!(statistics.nodes_generated, observer.generated);
+    assert_eq!(statistics.duplicates, observer.duplicates);
+    assert_eq!(statistics.solutions, observer.solutions.len());
+}
+
+#[test]
+fn observer_breadth_first() {
+    let observer = Rc::new(RefCell::new(CountingObserver::default()));
+    let mut search = Search::new_hashed();
+    search.set_observer(Rc::clone(&observer));
+    let initial_state = GraphState::build(grid_graph(4), 0);
+    let solution = search.search_breadth_first(initial_state).unwrap().unwrap();
+    let observer = observer.borrow();
+    assert_matches_statistics(&observer, &search.statistics);
+    assert!(observer.duplicates > 0);
+    assert_eq!(vec![solution.cost], observer.solutions);
+    assert!(observer.heuristics.iter().all(|h| h.is_none()));
+}
+
+#[test]
+fn observer_a_star() {
+    let observer = Rc::new(RefCell::new(CountingObserver::default()));
+    let mut search = Search::new_hashed();
+    search.set_observer(Rc::clone(&observer));
+    let initial_state = GraphState::build(grid_graph(4), 0);
+    let solution = search.search_a_start_first(initial_state).unwrap().unwrap();
+    let observer = observer.borrow();
+    assert_matches_statistics(&observer, &search.statistics);
+    assert_eq!(vec![solution.cost], observer.solutions);
+    assert!(observer.heuristics.iter().all(|h| h.is_some()));
+}
+
+#[test]
+fn observer_depth_cutoff() {
+    let observer = Rc::new(RefCell::new(CountingObserver::default()));
+    let mut search = Search::new_hashed();
+    search.set_observer(Rc::clone(&observer));
+    let initial_state = GraphState::build(grid_graph(4), 0);
+    assert!(search
+        .search_iter_depth_first(initial_state, 1)
+        .unwrap()
+        .is_some());
+    assert!(observer.borrow().cutoffs > 0);
+
+    search.remove_observer();
+    let cutoffs = observer.borrow().cutoffs;
+    search
+        .search_iter_depth_first(GraphState::build(grid_graph(4), 0), 1)
+        .unwrap();
+    assert_eq!(cutoffs, observer.borrow().cutoffs);
+}
+
+#[test]
+fn observer_bidirectional() {
+    let observer = Rc::new(RefCell::new(CountingObserver::default()));
+    let mut search = Search::new_hashed();
+    search.set_observer(Rc::clone(&observer));
+    let initial_state = GraphState::build(grid_graph(6), 0);
+    let goal_state = initial_state.with_node(35);
+    search
+        .search_bidirectional_breadth_first(initial_state.clone(), goal_state.clone())
+        .unwrap();
+    assert_eq!(search.statistics.nodes_explored, observer.borrow().expanded);
+    assert_eq!(1, observer.borrow().solutions.len());
+
+    observer.replace(CountingObserver::default());
+    let solution = search
+        .search_bidirectional_heuristic_first(initial_state, goal_state)
+        .unwrap()
+        .unwrap();
+    let observer = observer.borrow();
+    assert_eq!(search.statistics.nodes_explored, observer.expanded);
+    assert_eq!(search.statistics.duplicates, observer.duplicates);
+    assert_eq!(1, observer.solutions.len());
+    assert!(observer.solutions[0] <= solution.cost);
+    assert!(observer.heuristics.iter().all(|h| h.is_some()));
+}
+
+#[test]
+fn dot_export_a_star() {
+    let dot = Rc::new(RefCell::new(DotExport::new(|state: &GraphState| {
+        format!("node {}", state.node)
+    })));
+    dot.borrow_mut()
+        .set_action_format(|edge: &Edge| edge.cost.to_string());
+    let mut search = Search::new_hashed();
+    search.set_observer(Rc::clone(&dot));
+    let initial_state = GraphState::build(weighted_graph(), 0);
+    assert!(search
+        .search_a_start_first(initial_state)
+        .unwrap()
+        .is_some());
+
+    let dot = dot.borrow().to_dot();
+    assert!(dot.starts_with("digraph search {\n"));
+    assert!(dot.contains("n0 [label=\"node 0\\n#1\\ng=0 h=0 f=0\", shape=box, color=red"));
+    assert!(dot.contains("[label=\"node 4\\n#4\\ng=4 h=0 f=4\", shape=box, color=red"));
+    assert!(dot.contains("[label=\"2\", color=red, penwidth=2];"));
+    assert_eq!(5, dot.matches(" -> ").count());
+    assert_eq!(
+        3,
+        dot.lines()
+            .filter(|line| line.contains("->") && line.contains("color=red"))
+            .count()
+    );
+    assert!(dot.contains("[label=\"10\"];"));
+}
+
+#[test]
+fn dot_export_reopened_solution_path() {
+    let dot = Rc::new(RefCell::new(DotExport::new(|state: &GraphState| {
+        state.node.to_string()
+    })));
+    dot.borrow_mut()
+        .set_action_format(|edge: &Edge| format!("{}-{}", edge.from, edge.to));
+    let mut search = Search::new_hashed();
+    search.set_observer(Rc::clone(&dot));
+    let initial_state = GraphState::build(inconsistent_graph(), 0);
+    assert!(search
+        .search_a_start_first(initial_state)
+        .unwrap()
+        .is_some());
+
+    // node 2 is reopened through 1, which moves the path away from 0 -> 2
+    let dot = dot.borrow().to_dot();
+    let mut solution_edges: Vec<_> = dot
+        .lines()
+        .filter(|line| line.contains("->") && line.contains("color=red"))
+        .filter_map(|line| line.split('"').nth(1))
+        .collect();
+    solution_edges.sort();
+    assert_eq!(vec!["0-1", "1-2", "2-3"], solution_edges);
+    assert!(dot
+        .lines()
+        .any(|line| line.contains("[label=\"1\\n") && line.contains("color=red")));
+}
+
+#[test]
+fn dot_export_duplicates_and_cutoffs() {
+    let dot = Rc::new(RefCell::new(DotExport::new(|state: &GraphState| {
+        state.node.to_string()
+    })));
+    let mut search = Search::new_hashed();
+    search.set_observer(Rc::clone(&dot));
+    let initial_state = GraphState::build(grid_graph(3), 0);
+    assert!(search
+        .search_breadth_first(initial_state.clone())
+        .unwrap()
+        .is_some());
+    let breadth = dot.borrow().to_dot();
+    assert!(breadth.contains("style=dashed, color=gray"));
+    assert!(!breadth.contains("style=dotted"));
+
+    dot.borrow_mut().clear();
+    search.search_iter_depth_first(initial_state, 1).unwrap();
+    let iterative = dot.borrow().to_dot();
+    assert!(iterative.contains("style=dotted"));
+    assert_eq!(
+        4,
+        iterative
+            .lines()
+            .filter(|line| line.contains("->") && line.contains("color=red"))
+            .count()
+    );
+}
+
+/// Queue that drops the states added once it holds `capacity` of them
+struct BoundedQueue<T> {
+    queue: Queue<T>,
+    capacity: usize,
+}
+
+impl<T> OpenList<T> for BoundedQueue<T> {
+    fn new() -> Self {
+        BoundedQueue {
+            queue: Queue::new(),
+            capacity: usize::MAX,
+        }
+    }
+
+    fn add(&mut self, element: T) {
+        if self.queue.len() < self.capacity {
+            self.queue.add(element);
+        }
+    }
+
+    fn get(&mut self) -> Option<T> {
+        self.queue.get()
+    }
+
+    fn peek(&self) -> Option<&T> {
+        self.queue.peek()
+    }
+
+    fn is_empty(&self) -> bool {
+        self.queue.is_empty()
+    }
+
+    fn len(&self) -> usize {
+        self.queue.len()
+    }
+
+    fn clear(&mut self) {
+        self.queue.clear()
+    }
+}
+
+#[test]
+fn problem_1_queue_frontier() {
+    let initial_state = common::NumberState::build(vec![2, 4, 5, 7, 25], 855);
+    let mut search = Search::new_hashed();
+    let expected = search.search_breadth_all(initial_state.clone()).unwrap();
+    let expected_statistics = search.statistics.clone();
+
+    let mut open_list = Queue::new();
+    let solutions = search
+        .search_with_frontier(initial_state, &mut open_list, 0)
+        .unwrap();
+    assert_eq!(expected, solutions);
+    assert_eq!(
+        expected_statistics.nodes_explored,
+        search.statistics.nodes_explored
+    );
+    assert_eq!("custom_frontier", search.report().algorithm);
+}
+
+#[test]
+fn grid_bounded_frontier() {
+    let initial_state = GraphState::build(grid_graph(6), 0);
+    let mut search = Search::new_hashed();
+    let mut open_list = BoundedQueue::new();
+    open_list.capacity = 3;
+    let solution = search
+        .search_with_frontier(initial_state, &mut open_list, 1)
+        .unwrap();
+    assert_eq!(35, solution[0].solution_state().node);
+    assert!(search.statistics.max_open <= 3);
+}
+
+#[test]
+fn iter_depth_zero_step() {
+    let initial_state = common::NumberState::build(vec![2, 4], 3);
+    let mut search = Search::new();
+    let error = search
+        .search_iter_depth_first(initial_state, 0)
+        .unwrap_err();
+    assert_eq!("iterative_deepening", error.algorithm());
+    assert_eq!(
+        "iterative_deepening: invalid parameter, the limit step must be > 0",
+        error.to_string()
+    );
+}
+
+#[test]
+fn beam_zero_width() {
+    let initial_state = GraphState::build(grid_graph(3), 0);
+    let mut search = Search::new_hashed();
+    assert!(matches!(
+        search.search_beam_first(initial_state, 0, BeamOrder::Heuristic),
+        Err(SearchError::InvalidParameter {
+            algorithm: "beam",
+            ..
+        })
+    ));
+}
+
+/// Graph state whose domain panics when an action reaches node 4
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+struct Faulty(GraphState);
+
+impl State<Edge> for Faulty {
+    fn apply_action(&self, action: &Edge) -> Self {
+        assert!(action.to != 4, "invalid action {action:?}");
+        Faulty(self.0.apply_action(action))
+    }
+
+    fn get_applicable_actions(&self) -> Vec<Edge> {
+        self.0.get_applicable_actions()
+    }
+
+    fn is_solution(&self) -> bool {
+        self.0.is_solution()
+    }
+}
+
+impl StateHeuristic<Edge> for Faulty {
+    fn heuristic(&self) -> f32 {
+        self.0.heuristic()
+    }
+}
+
+#[test]
+fn panic_while_expanding() {
+    let initial_state = Faulty(GraphState::build(grid_graph(3), 0));
+    let mut search = Search::new_hashed();
+    let error = search
+        .search_breadth_first(initial_state.clone())
+        .unwrap_err();
+    assert!(matches!(
+        &error,
+        SearchError::Panicked { algorithm: "breadth_first", message } if message.starts_with("invalid action")
+    ));
+    assert!(error
+        .to_string()
+        .starts_with("breadth_first: panicked expanding a state, invalid action"));
+
+    let mut solutions = search.iter_depth(initial_state);
+    assert!(matches!(
+        solutions.next(),
+        Some(Err(SearchError::Panicked { .. }))
+    ));
+    assert!(solutions.next().is_none());
+}
+
+#[test]
+fn problem_1_hda_star_matches_a_star() {
+    let initial_state = common::NumberState::build(vec![2, 4, 5, 7, 25], 855);
+    let mut search = Search::new_hashed();
+    let expected = search
+        .search_a_start_first(initial_state.clone())
+        .unwrap()
+        .unwrap();
+    for threads in [1, 4] {
+        let mut parallel = ParallelSearch::new(threads);
+        let solution = parallel
+            .search_hda_star_first(initial_state.clone())
+            .unwrap()
+            .unwrap();
+        dbg!(&parallel.statistics);
+        assert_eq!(expected.cost, solution.cost);
+        assert_eq!(1, parallel.statistics.solutions);
+        assert!(solution.solution_state().is_solution());
+    }
+}
+
+#[test]
+fn grid_hda_star_first() {
+    let initial_state = GraphState::build(grid_graph(6), 0);
+    let mut parallel = ParallelSearch::new(4);
+    let solution = parallel
+        .search_hda_star_first(initial_state)
+        .unwrap()
+        .unwrap();
+    assert_eq!(10.0, solution.cost);
+    assert_eq!(35, solution.solution_state().node);
+    assert!(solution
+        .states
+        .iter()
+        .zip(&solution.actions)
+        .all(|(state, action)| state.node == action.from));
+    assert_eq!(
+        parallel.statistics.nodes_explored,
+        parallel.statistics.expanded_by_depth.iter().sum::<usize>()
+    );
+    // peak of the open lists together, not a sum of the peaks of each one
+    assert!(parallel.statistics.max_open > 0);
+    assert!(parallel.statistics.max_open <= parallel.statistics.nodes_generated);
+    assert_eq!("hda_star", parallel.report().algorithm);
+}
+
+#[test]
+fn weighted_graph_hda_star_on_other_thread() {
+    let mut parallel = ParallelSearch::new(3);
+    let solution = thread::spawn(move || {
+        let initial_state = GraphState::build(weighted_graph(), 0);
+        parallel.search_hda_star_first(initial_state).unwrap()
+    });
+    let solution = solution.join().unwrap().unwrap();
+    assert_eq!(6.0, solution.cost);
+    assert_eq!(3, solution.solution_state().node);
+    assert_eq!(
+        vec![0, 2, 4, 3],
+        solution.states.iter().map(|s| s.node).collect::<Vec<_>>()
+    );
+}
+
+#[test]
+fn hda_star_no_solution() {
+    let initial_state = common::NumberState::build(vec![2, 4], 3);
+    let mut parallel = ParallelSearch::new(2);
+    assert_eq!(Ok(None), parallel.search_hda_star_first(initial_state));
+    assert_eq!(0, parallel.statistics.solutions);
+}
+
+#[test]
+fn hda_star_cancelled_and_limited() {
+    let initial_state = GraphState::build(grid_graph(6), 0);
+    let mut parallel = ParallelSearch::new(2);
+    parallel.set_cancel_flag(Arc::new(AtomicBool::new(true)));
+    assert_eq!(
+        Err(SearchError::Cancelled {
+            algorithm: "hda_star",
+            partial: ()
+        }),
+        parallel.search_hda_star_first(initial_state.clone())
+    );
+    assert_eq!(0, parallel.statistics.nodes_explored);
+
+    // a single worker stops exactly at the limit
+    let mut parallel = ParallelSearch::new(1);
+    let limits = Limits {
+        max_nodes: Some(5),
+        ..Limits::default()
+    };
+    parallel.set_limits(limits);
+    assert_eq!(
+        Err(SearchError::LimitReached {
+            algorithm: "hda_star",
+            limit: Limit::Nodes,
+            partial: ()
+        }),
+        parallel.search_hda_star_first(initial_state)
+    );
+    assert_eq!(5, parallel.statistics.nodes_explored);
+    assert_eq!(limits, parallel.report().limits);
+}
+
+#[test]
+fn hda_star_errors() {
+    let initial_state = Faulty(GraphState::build(grid_graph(3), 0));
+    let mut parallel = ParallelSearch::new(0);
+    assert!(matches!(
+        parallel.search_hda_star_first(initial_state.clone()),
+        Err(SearchError::InvalidParameter {
+            algorithm: "hda_star",
+            ..
+        })
+    ));
+    let mut parallel = ParallelSearch::new(2);
+    assert!(matches!(
+        parallel.search_hda_star_first(initial_state),
+        Err(SearchError::Panicked {
+            algorithm: "hda_star",
+            ..
+        })
+    ));
 }